@@ -0,0 +1,674 @@
+//! 使用量告警逻辑
+//! 定义多级阈值、告警级别与通知渠道，负责匹配触发的阈值级别，
+//! 并维护每个级别的告警状态（去重、冷却与回差）、严重告警的升级重复提醒以及告警静音
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 默认消息模板
+/// 支持占位符：{usage} 当前使用量、{threshold} 阈值、{severity} 告警级别
+pub const DEFAULT_MESSAGE_TEMPLATE: &str = "当前使用量已达到 {usage}%（{severity}阈值 {threshold}%），请注意配额使用情况！";
+
+/// 告警级别
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+  /// 提醒
+  Info,
+  /// 预警
+  Warning,
+  /// 严重
+  Critical,
+}
+
+impl Severity {
+  /// 级别的中文名称，用于通知标题与正文
+  pub fn label(&self) -> &'static str {
+    match self {
+      Severity::Info => "提醒",
+      Severity::Warning => "预警",
+      Severity::Critical => "严重",
+    }
+  }
+
+  /// 级别的英文标识，与序列化值一致
+  pub fn label_key(&self) -> &'static str {
+    match self {
+      Severity::Info => "info",
+      Severity::Warning => "warning",
+      Severity::Critical => "critical",
+    }
+  }
+}
+
+/// 通知渠道
+/// 目前只支持系统通知与企业微信；邮件渠道需要 SMTP 配置与凭据管理，暂未实现
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationChannel {
+  /// 系统通知
+  System,
+  /// 企业微信 Webhook
+  WechatWork,
+}
+
+/// 全部通知渠道
+pub fn all_channels() -> Vec<NotificationChannel> {
+  vec![NotificationChannel::System, NotificationChannel::WechatWork]
+}
+
+/// 默认消息模板（serde 默认值）
+fn default_message_template() -> String {
+  DEFAULT_MESSAGE_TEMPLATE.to_string()
+}
+
+/// 阈值级别
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, Debug, PartialEq)]
+pub struct ThresholdTier {
+  /// 阈值（百分比），大于 0 且不超过 100
+  #[serde(rename = "threshold")]
+  #[schemars(extend("exclusiveMinimum" = 0, "maximum" = 100))]
+  pub threshold: f64,
+  /// 告警级别
+  #[serde(rename = "severity")]
+  pub severity: Severity,
+  /// 消息模板
+  #[serde(rename = "messageTemplate", default = "default_message_template")]
+  pub message_template: String,
+  /// 触发时使用的通知渠道
+  #[serde(rename = "channels", default = "all_channels")]
+  #[schemars(length(min = 1))]
+  pub channels: Vec<NotificationChannel>,
+}
+
+impl ThresholdTier {
+  /// 创建使用默认模板的阈值级别
+  pub fn new(threshold: f64, severity: Severity, channels: Vec<NotificationChannel>) -> Self {
+    Self {
+      threshold,
+      severity,
+      message_template: default_message_template(),
+      channels,
+    }
+  }
+
+  /// 告警状态中使用的唯一标识，例如 "warning@85.0"
+  /// 阈值按精确值（最短的可还原表示）格式化，92.2 与 92.25 不会共用同一个状态
+  pub fn key(&self) -> String {
+    format!("{}@{:?}", self.severity.label_key(), self.threshold)
+  }
+
+  /// 渲染通知正文
+  pub fn render_message(&self, usage: f64) -> String {
+    self.message_template
+      .replace("{usage}", &format!("{:.1}", usage))
+      .replace("{threshold}", &format!("{:.1}", self.threshold))
+      .replace("{severity}", self.severity.label())
+  }
+}
+
+/// 默认阈值级别：70% 提醒、85% 预警、95% 严重
+pub fn default_tiers() -> Vec<ThresholdTier> {
+  vec![
+    ThresholdTier::new(70.0, Severity::Info, vec![NotificationChannel::System]),
+    ThresholdTier::new(85.0, Severity::Warning, all_channels()),
+    ThresholdTier::new(95.0, Severity::Critical, all_channels()),
+  ]
+}
+
+/// 某一级别通知使用的渠道：该级别各阈值配置的渠道之和，按 all_channels 的顺序排列；
+/// 没有配置该级别的阈值时使用全部渠道
+pub fn severity_channels(tiers: &[ThresholdTier], severity: Severity) -> Vec<NotificationChannel> {
+  let configured: Vec<&ThresholdTier> = tiers.iter().filter(|tier| tier.severity == severity).collect();
+  if configured.is_empty() {
+    return all_channels();
+  }
+  all_channels()
+    .into_iter()
+    .filter(|channel| configured.iter().any(|tier| tier.channels.contains(channel)))
+    .collect()
+}
+
+/// 单个阈值级别的告警状态
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct TierAlertState {
+  /// 是否已触发（触发后需回落到阈值减回差以下才会重新布防）
+  #[serde(rename = "fired")]
+  pub fired: bool,
+  /// 最近一次发送通知的时间（Unix 毫秒）
+  #[serde(rename = "lastNotifiedAt", default)]
+  pub last_notified_at: Option<u64>,
+}
+
+/// 严重告警的升级状态
+/// 触发严重级别后按间隔重复提醒，直到用户确认、使用量回落或配额重置
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct EscalationState {
+  /// 触发的阈值级别，键为 ThresholdTier::key()
+  #[serde(rename = "tierKey")]
+  pub tier_key: String,
+  /// 阈值（百分比），用于提醒文案
+  #[serde(rename = "threshold")]
+  pub threshold: f64,
+  /// 开始时间（Unix 毫秒）
+  #[serde(rename = "startedAt")]
+  pub started_at: u64,
+  /// 最近一次提醒时间（Unix 毫秒）
+  #[serde(rename = "lastSentAt")]
+  pub last_sent_at: u64,
+  /// 已重复提醒的次数（不含首次告警）
+  #[serde(rename = "repeats")]
+  pub repeats: u32,
+  /// 是否已被用户确认
+  #[serde(rename = "acknowledged")]
+  pub acknowledged: bool,
+}
+
+/// 默认升级步骤：第一组（首次告警）只有系统通知，第 1 次重复提醒起加上企业微信
+pub fn default_escalation_steps() -> Vec<Vec<NotificationChannel>> {
+  vec![
+    vec![NotificationChannel::System],
+    vec![NotificationChannel::System, NotificationChannel::WechatWork],
+  ]
+}
+
+/// 告警状态
+/// 按阈值级别记录，持久化到数据目录，重启后不会重复告警
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct AlertState {
+  /// 各级别的状态，键为 ThresholdTier::key()
+  #[serde(rename = "tiers", default)]
+  pub tiers: HashMap<String, TierAlertState>,
+  /// 正在进行的严重告警升级
+  #[serde(rename = "escalation", default)]
+  pub escalation: Option<EscalationState>,
+  /// 告警静音
+  #[serde(rename = "snooze", default)]
+  pub snooze: SnoozeState,
+}
+
+/// 一条生效中的静音
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ActiveSnooze {
+  /// 静音的级别，None 表示全部级别
+  #[serde(rename = "tier")]
+  pub tier: Option<Severity>,
+  /// 静音结束时间（Unix 毫秒）
+  #[serde(rename = "until")]
+  pub until: u64,
+}
+
+/// 告警静音状态
+/// 可以静音全部告警，也可以只静音某个级别；到期后自动失效
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct SnoozeState {
+  /// 全部告警静音到该时间（Unix 毫秒）
+  #[serde(rename = "allUntil", default)]
+  pub all_until: Option<u64>,
+  /// 按级别静音到的时间（Unix 毫秒）
+  #[serde(rename = "tiers", default)]
+  pub tiers: HashMap<Severity, u64>,
+}
+
+impl SnoozeState {
+  /// 静音到指定时间，`severity` 为 None 时静音全部告警
+  pub fn snooze(&mut self, severity: Option<Severity>, until: u64) {
+    match severity {
+      Some(severity) => {
+        self.tiers.insert(severity, until);
+      }
+      None => self.all_until = Some(until),
+    }
+  }
+
+  /// 取消静音，`severity` 为 None 时取消所有静音
+  pub fn unsnooze(&mut self, severity: Option<Severity>) {
+    match severity {
+      Some(severity) => {
+        self.tiers.remove(&severity);
+      }
+      None => *self = Self::default(),
+    }
+  }
+
+  /// 全部告警是否处于静音中
+  pub fn is_all_snoozed(&self, now: u64) -> bool {
+    self.all_until.is_some_and(|until| now < until)
+  }
+
+  /// 指定级别是否处于静音中（包括全部静音）
+  pub fn is_snoozed(&self, severity: Severity, now: u64) -> bool {
+    self.is_all_snoozed(now) || self.tiers.get(&severity).is_some_and(|until| now < *until)
+  }
+
+  /// 清理已过期的静音，返回是否有变化
+  pub fn prune(&mut self, now: u64) -> bool {
+    let before = (self.all_until, self.tiers.len());
+    if !self.is_all_snoozed(now) {
+      self.all_until = None;
+    }
+    self.tiers.retain(|_, until| now < *until);
+    before != (self.all_until, self.tiers.len())
+  }
+
+  /// 列出生效中的静音
+  pub fn active(&self, now: u64) -> Vec<ActiveSnooze> {
+    let mut active: Vec<ActiveSnooze> = self
+      .tiers
+      .iter()
+      .filter(|(_, until)| now < **until)
+      .map(|(severity, until)| ActiveSnooze { tier: Some(*severity), until: *until })
+      .collect();
+    active.sort_by_key(|snooze| snooze.tier);
+    if let Some(until) = self.all_until.filter(|until| now < *until) {
+      active.insert(0, ActiveSnooze { tier: None, until });
+    }
+    active
+  }
+}
+
+/// 一次告警评估的结果
+#[derive(Debug, Default)]
+pub struct AlertEvaluation {
+  /// 需要发送通知的最高级别（同时越过多个级别时只通知最高的一个）
+  pub fired: Option<ThresholdTier>,
+  /// 本次回落到回差以下、重新布防的级别
+  pub rearmed: Vec<ThresholdTier>,
+  /// 本应通知但处于静音中的级别（保持布防，静音结束后的读数仍会触发）
+  pub suppressed: Vec<ThresholdTier>,
+  /// 状态是否发生变化，需要持久化
+  pub changed: bool,
+}
+
+impl AlertState {
+  /// 根据当前使用量评估各级别告警
+  /// - 首次越过阈值时触发一次
+  /// - 使用量回落到 `threshold - hysteresis` 以下时重新布防
+  /// - `cooldown` 大于 0 时，持续高于阈值且距上次通知超过冷却时间会再次通知
+  /// - 处于静音中的级别不记为已触发，也不会开始升级提醒，静音结束后仍高于阈值时再通知
+  pub fn evaluate(
+    &mut self,
+    tiers: &[ThresholdTier],
+    usage: f64,
+    now: u64,
+    hysteresis: f64,
+    cooldown: Duration,
+  ) -> AlertEvaluation {
+    let mut evaluation = AlertEvaluation::default();
+    let cooldown_millis = cooldown.as_millis() as u64;
+
+    for tier in tiers {
+      let state = self.tiers.entry(tier.key()).or_default();
+
+      if usage >= tier.threshold {
+        let should_notify = if !state.fired {
+          true
+        } else {
+          cooldown_millis > 0
+            && state
+              .last_notified_at
+              .map_or(true, |last| now.saturating_sub(last) >= cooldown_millis)
+        };
+
+        if should_notify && self.snooze.is_snoozed(tier.severity, now) {
+          evaluation.suppressed.push(tier.clone());
+        } else if should_notify {
+          state.fired = true;
+          state.last_notified_at = Some(now);
+          evaluation.changed = true;
+
+          let is_higher = evaluation
+            .fired
+            .as_ref()
+            .map_or(true, |current| tier.threshold > current.threshold);
+          if is_higher {
+            evaluation.fired = Some(tier.clone());
+          }
+        }
+      } else if state.fired && usage < tier.threshold - hysteresis.max(0.0) {
+        state.fired = false;
+        evaluation.changed = true;
+        evaluation.rearmed.push(tier.clone());
+      }
+    }
+
+    // 严重级别首次触发时开始升级提醒，回落后结束
+    if let Some(tier) = &evaluation.fired {
+      if tier.severity == Severity::Critical && self.escalation.is_none() {
+        self.escalation = Some(EscalationState {
+          tier_key: tier.key(),
+          threshold: tier.threshold,
+          started_at: now,
+          last_sent_at: now,
+          repeats: 0,
+          acknowledged: false,
+        });
+      }
+    }
+    let escalation_rearmed = self.escalation.as_ref().is_some_and(|escalation| {
+      evaluation.rearmed.iter().any(|tier| tier.key() == escalation.tier_key)
+    });
+    if escalation_rearmed {
+      self.escalation = None;
+    }
+
+    if self.retain_tiers(tiers) {
+      evaluation.changed = true;
+    }
+
+    evaluation
+  }
+
+  /// 清理已不在配置中的级别（如切换配置方案后），其升级提醒也随之结束；返回状态是否变化
+  pub fn retain_tiers(&mut self, tiers: &[ThresholdTier]) -> bool {
+    let keys: Vec<String> = tiers.iter().map(ThresholdTier::key).collect();
+    let before = self.tiers.len();
+    self.tiers.retain(|key, _| keys.contains(key));
+    let orphaned = self
+      .escalation
+      .as_ref()
+      .is_some_and(|escalation| !keys.contains(&escalation.tier_key));
+    if orphaned {
+      self.escalation = None;
+    }
+    orphaned || self.tiers.len() != before
+  }
+
+  /// 重新布防所有级别（如配额已重置），同时结束升级提醒
+  pub fn rearm_all(&mut self) {
+    self.tiers.clear();
+    self.escalation = None;
+  }
+
+  /// 确认当前的严重告警，停止重复提醒
+  /// 没有需要确认的告警时返回 false
+  pub fn acknowledge(&mut self) -> bool {
+    match &mut self.escalation {
+      Some(escalation) if !escalation.acknowledged => {
+        escalation.acknowledged = true;
+        true
+      }
+      _ => false,
+    }
+  }
+
+  /// 检查是否需要发送下一次升级提醒
+  /// 到期时更新状态并返回 (第几次重复, 使用的渠道)；第一步对应首次告警，
+  /// 第 n 次重复使用第 n + 1 步（下标 n），超出后沿用最后一步
+  pub fn due_escalation(
+    &mut self,
+    now: u64,
+    repeat_interval: Duration,
+    steps: &[Vec<NotificationChannel>],
+  ) -> Option<(u32, Vec<NotificationChannel>)> {
+    if repeat_interval.is_zero() || steps.is_empty() {
+      return None;
+    }
+
+    let escalation = self.escalation.as_mut()?;
+    if escalation.acknowledged
+      || now.saturating_sub(escalation.last_sent_at) < repeat_interval.as_millis() as u64
+    {
+      return None;
+    }
+
+    escalation.repeats += 1;
+    escalation.last_sent_at = now;
+    let step = (escalation.repeats as usize).min(steps.len() - 1);
+    Some((escalation.repeats, steps[step].clone()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tier_key_combines_severity_and_threshold() {
+    assert_eq!(ThresholdTier::new(85.0, Severity::Warning, all_channels()).key(), "warning@85.0");
+    assert_eq!(ThresholdTier::new(92.25, Severity::Critical, all_channels()).key(), "critical@92.25");
+    assert_eq!(ThresholdTier::new(92.2, Severity::Critical, all_channels()).key(), "critical@92.2");
+  }
+
+  #[test]
+  fn render_message_fills_placeholders() {
+    let mut tier = ThresholdTier::new(85.0, Severity::Warning, all_channels());
+    tier.message_template = "{severity}: {usage}% / {threshold}%".to_string();
+    assert_eq!(tier.render_message(87.26), "预警: 87.3% / 85.0%");
+  }
+
+  #[test]
+  fn severity_channels_follow_configured_tiers() {
+    let tiers = default_tiers();
+    assert_eq!(severity_channels(&tiers, Severity::Info), vec![NotificationChannel::System]);
+    assert_eq!(severity_channels(&tiers, Severity::Critical), all_channels());
+
+    let tiers = vec![
+      ThresholdTier::new(80.0, Severity::Warning, vec![NotificationChannel::WechatWork]),
+      ThresholdTier::new(90.0, Severity::Warning, vec![NotificationChannel::System]),
+    ];
+    assert_eq!(severity_channels(&tiers, Severity::Warning), all_channels());
+    // 没有配置该级别时使用全部渠道
+    assert_eq!(severity_channels(&tiers, Severity::Info), all_channels());
+  }
+
+  #[test]
+  fn tier_defaults_template_and_channels_when_missing() {
+    let tier: ThresholdTier = serde_json::from_str(r#"{"threshold": 80, "severity": "info"}"#).unwrap();
+    assert_eq!(tier.message_template, DEFAULT_MESSAGE_TEMPLATE);
+    assert_eq!(tier.channels, all_channels());
+  }
+
+  const NO_COOLDOWN: Duration = Duration::ZERO;
+
+  #[test]
+  fn crossing_fires_once_until_rearmed() {
+    let tiers = default_tiers();
+    let mut state = AlertState::default();
+
+    let first = state.evaluate(&tiers, 86.0, 0, 5.0, NO_COOLDOWN);
+    assert_eq!(first.fired.map(|tier| tier.severity), Some(Severity::Warning));
+    assert!(first.changed);
+
+    let repeat = state.evaluate(&tiers, 88.0, 1_000, 5.0, NO_COOLDOWN);
+    assert!(repeat.fired.is_none());
+    assert!(!repeat.changed);
+  }
+
+  #[test]
+  fn hysteresis_controls_rearm() {
+    let tiers = vec![ThresholdTier::new(85.0, Severity::Warning, all_channels())];
+    let mut state = AlertState::default();
+    state.evaluate(&tiers, 90.0, 0, 5.0, NO_COOLDOWN);
+
+    // 落在回差范围内不重新布防
+    let inside = state.evaluate(&tiers, 81.0, 1, 5.0, NO_COOLDOWN);
+    assert!(inside.rearmed.is_empty());
+    assert!(state.evaluate(&tiers, 86.0, 2, 5.0, NO_COOLDOWN).fired.is_none());
+
+    let below = state.evaluate(&tiers, 79.9, 3, 5.0, NO_COOLDOWN);
+    assert_eq!(below.rearmed.len(), 1);
+    assert!(state.evaluate(&tiers, 86.0, 4, 5.0, NO_COOLDOWN).fired.is_some());
+  }
+
+  #[test]
+  fn cooldown_renotifies_while_above_threshold() {
+    let tiers = vec![ThresholdTier::new(85.0, Severity::Warning, all_channels())];
+    let cooldown = Duration::from_secs(60);
+    let mut state = AlertState::default();
+    assert!(state.evaluate(&tiers, 90.0, 0, 5.0, cooldown).fired.is_some());
+    assert!(state.evaluate(&tiers, 90.0, 59_999, 5.0, cooldown).fired.is_none());
+    assert!(state.evaluate(&tiers, 90.0, 60_000, 5.0, cooldown).fired.is_some());
+  }
+
+  #[test]
+  fn jumping_past_several_tiers_notifies_highest_only() {
+    let tiers = default_tiers();
+    let mut state = AlertState::default();
+    let evaluation = state.evaluate(&tiers, 97.0, 0, 5.0, NO_COOLDOWN);
+    assert_eq!(evaluation.fired.map(|tier| tier.severity), Some(Severity::Critical));
+    assert!(state.tiers.values().all(|tier| tier.fired));
+  }
+
+  #[test]
+  fn removed_tiers_are_pruned() {
+    let mut state = AlertState::default();
+    state.evaluate(&default_tiers(), 90.0, 0, 5.0, NO_COOLDOWN);
+    let tiers = vec![ThresholdTier::new(80.0, Severity::Warning, all_channels())];
+    state.evaluate(&tiers, 50.0, 1, 5.0, NO_COOLDOWN);
+    assert_eq!(state.tiers.keys().collect::<Vec<_>>(), vec!["warning@80.0"]);
+  }
+
+  #[test]
+  fn escalation_ends_when_its_tier_is_removed() {
+    let mut state = AlertState::default();
+    state.evaluate(&default_tiers(), 97.0, 0, 5.0, NO_COOLDOWN);
+    assert!(state.escalation.is_some());
+
+    // 切换到只有更高严重级别的方案：原来的升级提醒不会再因回落而结束，需要随级别一起清理
+    let tiers = vec![ThresholdTier::new(99.0, Severity::Critical, all_channels())];
+    assert!(state.retain_tiers(&tiers));
+    assert!(state.escalation.is_none());
+    assert!(state.tiers.is_empty());
+    assert!(!state.retain_tiers(&tiers));
+  }
+
+  #[test]
+  fn escalation_survives_when_its_tier_is_kept() {
+    let mut state = AlertState::default();
+    state.evaluate(&default_tiers(), 97.0, 0, 5.0, NO_COOLDOWN);
+    assert!(!state.retain_tiers(&default_tiers()));
+    assert!(state.escalation.is_some());
+  }
+
+  #[test]
+  fn rearm_all_allows_firing_again() {
+    let tiers = default_tiers();
+    let mut state = AlertState::default();
+    state.evaluate(&tiers, 90.0, 0, 5.0, NO_COOLDOWN);
+    state.rearm_all();
+    assert!(state.evaluate(&tiers, 90.0, 1, 5.0, NO_COOLDOWN).fired.is_some());
+  }
+
+  fn critical_state(now: u64) -> AlertState {
+    let mut state = AlertState::default();
+    state.evaluate(&default_tiers(), 96.0, now, 5.0, NO_COOLDOWN);
+    state
+  }
+
+  #[test]
+  fn critical_crossing_starts_escalation() {
+    let state = critical_state(0);
+    let escalation = state.escalation.as_ref().unwrap();
+    assert_eq!(escalation.tier_key, "critical@95.0");
+    assert_eq!(escalation.repeats, 0);
+  }
+
+  #[test]
+  fn escalation_repeats_and_adds_channels() {
+    let steps = default_escalation_steps();
+    let interval = Duration::from_secs(15 * 60);
+    let mut state = critical_state(0);
+
+    assert!(state.due_escalation(14 * 60_000, interval, &steps).is_none());
+    // 第一步对应首次告警，第 1 次重复就加上企业微信
+    assert_eq!(
+      state.due_escalation(15 * 60_000, interval, &steps),
+      Some((1, vec![NotificationChannel::System, NotificationChannel::WechatWork]))
+    );
+    assert!(state.due_escalation(20 * 60_000, interval, &steps).is_none());
+    // 超出步骤后沿用最后一步
+    assert_eq!(state.due_escalation(30 * 60_000, interval, &steps), Some((2, steps[1].clone())));
+  }
+
+  #[test]
+  fn escalation_walks_the_configured_schedule() {
+    let steps = vec![
+      vec![NotificationChannel::System],
+      vec![NotificationChannel::WechatWork],
+      vec![NotificationChannel::System, NotificationChannel::WechatWork],
+    ];
+    let interval = Duration::from_secs(60);
+    let mut state = critical_state(0);
+    assert_eq!(state.due_escalation(60_000, interval, &steps), Some((1, steps[1].clone())));
+    assert_eq!(state.due_escalation(120_000, interval, &steps), Some((2, steps[2].clone())));
+    assert_eq!(state.due_escalation(180_000, interval, &steps), Some((3, steps[2].clone())));
+  }
+
+  #[test]
+  fn acknowledged_escalation_stops_repeating() {
+    let steps = default_escalation_steps();
+    let mut state = critical_state(0);
+    assert!(state.acknowledge());
+    assert!(!state.acknowledge());
+    assert!(state.due_escalation(u64::MAX, Duration::from_secs(60), &steps).is_none());
+  }
+
+  #[test]
+  fn escalation_ends_when_usage_drops() {
+    let mut state = critical_state(0);
+    state.evaluate(&default_tiers(), 80.0, 1, 5.0, NO_COOLDOWN);
+    assert!(state.escalation.is_none());
+  }
+
+  #[test]
+  fn zero_repeat_interval_disables_escalation() {
+    let mut state = critical_state(0);
+    assert!(state.due_escalation(u64::MAX, Duration::ZERO, &default_escalation_steps()).is_none());
+    assert!(state.due_escalation(u64::MAX, Duration::from_secs(60), &[]).is_none());
+  }
+
+  #[test]
+  fn snoozed_crossing_stays_armed_until_snooze_ends() {
+    let tiers = vec![ThresholdTier::new(85.0, Severity::Warning, all_channels())];
+    let mut state = AlertState::default();
+    state.snooze.snooze(Some(Severity::Warning), 1_000);
+
+    let during = state.evaluate(&tiers, 90.0, 500, 5.0, NO_COOLDOWN);
+    assert!(during.fired.is_none());
+    assert_eq!(during.suppressed.len(), 1);
+
+    let after = state.evaluate(&tiers, 90.0, 1_000, 5.0, NO_COOLDOWN);
+    assert_eq!(after.fired.map(|tier| tier.severity), Some(Severity::Warning));
+  }
+
+  #[test]
+  fn snoozed_critical_does_not_start_escalation() {
+    let mut state = AlertState::default();
+    state.snooze.snooze(None, 1_000);
+    let evaluation = state.evaluate(&default_tiers(), 96.0, 0, 5.0, NO_COOLDOWN);
+    assert!(evaluation.fired.is_none());
+    assert_eq!(evaluation.suppressed.len(), 3);
+    assert!(state.escalation.is_none());
+
+    state.evaluate(&default_tiers(), 96.0, 1_000, 5.0, NO_COOLDOWN);
+    assert!(state.escalation.is_some());
+  }
+
+  #[test]
+  fn snoozing_one_tier_still_notifies_lower_tiers() {
+    let mut state = AlertState::default();
+    state.snooze.snooze(Some(Severity::Critical), 1_000);
+    let evaluation = state.evaluate(&default_tiers(), 96.0, 0, 5.0, NO_COOLDOWN);
+    assert_eq!(evaluation.fired.map(|tier| tier.severity), Some(Severity::Warning));
+    assert!(state.escalation.is_none());
+  }
+
+  #[test]
+  fn snooze_expiry_and_listing() {
+    let mut snooze = SnoozeState::default();
+    snooze.snooze(Some(Severity::Info), 100);
+    snooze.snooze(None, 50);
+    assert!(snooze.is_snoozed(Severity::Critical, 49));
+    assert!(!snooze.is_snoozed(Severity::Critical, 50));
+    assert!(snooze.is_snoozed(Severity::Info, 99));
+    assert_eq!(snooze.active(10).len(), 2);
+
+    assert!(snooze.prune(60));
+    assert_eq!(snooze.active(60).len(), 1);
+    assert!(!snooze.prune(60));
+    snooze.unsnooze(None);
+    assert!(snooze.active(0).is_empty());
+  }
+}
+
@@ -0,0 +1,180 @@
+//! 配置导入导出
+//! 导出的配置包可以不包含密钥（如企业微信 Webhook 地址），便于团队分发统一的预设；
+//! 导入时预设中的字段覆盖当前配置，默认保留本机自己的密钥
+
+use crate::AppConfig;
+use serde_json::{Map, Value};
+
+/// 配置包格式标识
+const BUNDLE_FORMAT: &str = "minmax-helper-settings";
+/// 配置包格式版本
+const BUNDLE_VERSION: u32 = 1;
+
+/// 视为密钥的配置字段
+pub const SECRET_FIELDS: &[&str] = &["wechatWorkWebhookUrl"];
+/// 只对本机有意义、不导出也不导入的字段
+pub const LOCAL_FIELDS: &[&str] = &["activeProfile"];
+
+/// 配置包
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct SettingsBundle {
+  /// 格式标识，固定为 minmax-helper-settings
+  #[serde(rename = "format")]
+  pub format: String,
+  /// 配置包格式版本
+  #[serde(rename = "bundleVersion")]
+  pub bundle_version: u32,
+  /// 导出时间（Unix 毫秒）
+  #[serde(rename = "exportedAt")]
+  pub exported_at: u64,
+  /// 是否包含密钥
+  #[serde(rename = "includesSecrets")]
+  pub includes_secrets: bool,
+  /// 配置内容，可以只包含部分字段
+  #[serde(rename = "settings")]
+  pub settings: Map<String, Value>,
+}
+
+/// 导出配置包
+/// `include_secrets` 为 false 时去掉密钥字段
+pub fn export_bundle(config: &AppConfig, include_secrets: bool, now_millis: u64) -> Result<SettingsBundle, String> {
+  let mut settings = match serde_json::to_value(config).map_err(|e| format!("配置序列化失败: {}", e))? {
+    Value::Object(settings) => settings,
+    _ => return Err("配置序列化结果不是对象".to_string()),
+  };
+  for field in LOCAL_FIELDS {
+    settings.remove(*field);
+  }
+  if !include_secrets {
+    for field in SECRET_FIELDS {
+      settings.remove(*field);
+    }
+  }
+
+  Ok(SettingsBundle {
+    format: BUNDLE_FORMAT.to_string(),
+    bundle_version: BUNDLE_VERSION,
+    exported_at: now_millis,
+    includes_secrets: include_secrets,
+    settings,
+  })
+}
+
+/// 解析配置包
+pub fn parse_bundle(content: &str) -> Result<SettingsBundle, String> {
+  let bundle: SettingsBundle = serde_json::from_str(content).map_err(|e| format!("配置包解析失败: {}", e))?;
+  if bundle.format != BUNDLE_FORMAT {
+    return Err(format!("不是有效的配置包（format 为 {}）", bundle.format));
+  }
+  if bundle.bundle_version > BUNDLE_VERSION {
+    return Err(format!(
+      "配置包版本 {} 高于当前支持的版本 {}，请升级应用",
+      bundle.bundle_version, BUNDLE_VERSION
+    ));
+  }
+  Ok(bundle)
+}
+
+/// 将配置包合并到当前配置
+/// 配置包中的字段覆盖当前配置；密钥字段只有在 `overwrite_secrets` 为 true 时才覆盖
+pub fn merge_bundle(current: &AppConfig, bundle: SettingsBundle, overwrite_secrets: bool) -> Result<AppConfig, String> {
+  let mut preset = Value::Object(bundle.settings);
+  crate::migration::migrate_config(&mut preset)?;
+
+  let mut merged = serde_json::to_value(current).map_err(|e| format!("配置序列化失败: {}", e))?;
+  if let (Value::Object(merged), Value::Object(preset)) = (&mut merged, preset) {
+    for (key, value) in preset {
+      if LOCAL_FIELDS.contains(&key.as_str()) || (!overwrite_secrets && SECRET_FIELDS.contains(&key.as_str())) {
+        continue;
+      }
+      merged.insert(key, value);
+    }
+  }
+
+  serde_json::from_value(merged).map_err(|e| format!("配置包内容无效: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::{local_config, WEBHOOK};
+  use serde_json::json;
+
+  fn bundle(settings: Value) -> SettingsBundle {
+    SettingsBundle {
+      format: BUNDLE_FORMAT.to_string(),
+      bundle_version: BUNDLE_VERSION,
+      exported_at: 0,
+      includes_secrets: false,
+      settings: settings.as_object().cloned().unwrap_or_default(),
+    }
+  }
+
+  #[test]
+  fn export_strips_secrets_and_local_fields() {
+    let bundle = export_bundle(&local_config(), false, 42).unwrap();
+    assert_eq!(bundle.exported_at, 42);
+    assert!(!bundle.includes_secrets);
+    assert!(!bundle.settings.contains_key("wechatWorkWebhookUrl"));
+    assert!(!bundle.settings.contains_key("activeProfile"));
+    assert_eq!(bundle.settings["checkInterval"], json!(5));
+  }
+
+  #[test]
+  fn export_can_include_secrets() {
+    let bundle = export_bundle(&local_config(), true, 0).unwrap();
+    assert!(bundle.includes_secrets);
+    assert_eq!(bundle.settings["wechatWorkWebhookUrl"], json!(WEBHOOK));
+    assert!(!bundle.settings.contains_key("activeProfile"));
+  }
+
+  #[test]
+  fn exported_bundle_round_trips() {
+    let content = serde_json::to_string(&export_bundle(&local_config(), false, 0).unwrap()).unwrap();
+    let parsed = parse_bundle(&content).unwrap();
+    let merged = merge_bundle(&AppConfig::default(), parsed, false).unwrap();
+    assert_eq!(merged.check_interval, 5);
+    assert_eq!(merged.wechat_work_webhook_url, AppConfig::default().wechat_work_webhook_url);
+  }
+
+  #[test]
+  fn parse_rejects_foreign_or_newer_bundles() {
+    assert!(parse_bundle("not json").is_err());
+    let mut other = bundle(json!({}));
+    other.format = "something-else".to_string();
+    assert!(parse_bundle(&serde_json::to_string(&other).unwrap()).is_err());
+    let mut newer = bundle(json!({}));
+    newer.bundle_version = BUNDLE_VERSION + 1;
+    assert!(parse_bundle(&serde_json::to_string(&newer).unwrap()).is_err());
+  }
+
+  #[test]
+  fn merge_keeps_local_secrets_and_profile() {
+    let preset = bundle(json!({
+      "checkInterval": 10,
+      "wechatWorkWebhookUrl": "https://example.com/other",
+      "activeProfile": "other",
+    }));
+    let merged = merge_bundle(&local_config(), preset.clone(), false).unwrap();
+    assert_eq!(merged.check_interval, 10);
+    assert_eq!(merged.wechat_work_webhook_url, WEBHOOK);
+    assert_eq!(merged.active_profile.as_deref(), Some("work"));
+
+    let merged = merge_bundle(&local_config(), preset, true).unwrap();
+    assert_eq!(merged.wechat_work_webhook_url, "https://example.com/other");
+    assert_eq!(merged.active_profile.as_deref(), Some("work"));
+  }
+
+  #[test]
+  fn merge_migrates_legacy_presets() {
+    let merged = merge_bundle(&local_config(), bundle(json!({ "warningThreshold": 70.0 })), false).unwrap();
+    assert_eq!(merged.threshold_tiers.len(), 1);
+    assert_eq!(merged.threshold_tiers[0].threshold, 70.0);
+    assert_eq!(merged.check_interval, 5);
+  }
+
+  #[test]
+  fn merge_rejects_invalid_values() {
+    assert!(merge_bundle(&local_config(), bundle(json!({ "checkInterval": "often" })), false).is_err());
+  }
+}
@@ -0,0 +1,310 @@
+//! 使用量趋势预测
+//! 对最近的读数做线性拟合，估算消耗速度与用满 100% 的时间，
+//! 并检测最近一段时间内的异常突增
+
+/// 拟合所需的最少读数
+const MIN_SAMPLES: usize = 3;
+/// 拟合所需的最短时间跨度（毫秒），避免短时间内的噪声
+const MIN_SPAN_MILLIS: u64 = 10 * 60 * 1000;
+
+/// 判定突增所需的最小增量（百分点），避免读数间隔很短时的噪声
+const MIN_JUMP_DELTA: f64 = 2.0;
+
+/// 预测结果
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Forecast {
+  /// 最近一次使用量（百分比）
+  #[serde(rename = "currentPercent")]
+  pub current_percent: f64,
+  /// 消耗速度（百分点 / 小时），没有足够数据时为 None
+  #[serde(rename = "ratePerHour")]
+  pub rate_per_hour: Option<f64>,
+  /// 预计用满 100% 的时间（Unix 毫秒），速度不为正时为 None
+  #[serde(rename = "projectedExhaustionAt")]
+  pub projected_exhaustion_at: Option<u64>,
+  /// 配额重置时间（Unix 毫秒），未知时为 None
+  #[serde(rename = "resetAt")]
+  pub reset_at: Option<u64>,
+  /// 是否会在重置之前用满
+  #[serde(rename = "exhaustsBeforeReset")]
+  pub exhausts_before_reset: bool,
+  /// 参与拟合的读数个数
+  #[serde(rename = "sampleCount")]
+  pub sample_count: usize,
+}
+
+/// 最小二乘线性拟合，返回斜率（百分点 / 毫秒）
+/// 读数不足或时间跨度太短时返回 None
+fn fit_slope(samples: &[(u64, f64)]) -> Option<f64> {
+  if samples.len() < MIN_SAMPLES {
+    return None;
+  }
+
+  let first = samples.first()?.0;
+  let last = samples.last()?.0;
+  if last.saturating_sub(first) < MIN_SPAN_MILLIS {
+    return None;
+  }
+
+  // 以第一个读数为原点，避免时间戳过大导致精度损失
+  let n = samples.len() as f64;
+  let xs: Vec<f64> = samples.iter().map(|(t, _)| t.saturating_sub(first) as f64).collect();
+  let mean_x = xs.iter().sum::<f64>() / n;
+  let mean_y = samples.iter().map(|(_, p)| p).sum::<f64>() / n;
+
+  let mut numerator = 0.0;
+  let mut denominator = 0.0;
+  for (x, (_, y)) in xs.iter().zip(samples) {
+    numerator += (x - mean_x) * (y - mean_y);
+    denominator += (x - mean_x) * (x - mean_x);
+  }
+
+  if denominator == 0.0 {
+    None
+  } else {
+    Some(numerator / denominator)
+  }
+}
+
+/// 根据最近读数计算预测
+/// `samples` 为按时间升序排列的 (Unix 毫秒, 百分比)，只使用最后一个读数之前 `window_millis` 内的读数；
+/// 预测以最后一个读数的时间为起点，数据较旧时不会把中间的空白当作没有消耗
+pub fn forecast(samples: &[(u64, f64)], window_millis: u64, reset_at: Option<u64>) -> Option<Forecast> {
+  let &(last_at, current_percent) = samples.last()?;
+
+  let since = last_at.saturating_sub(window_millis);
+  let recent: Vec<(u64, f64)> = samples.iter().copied().filter(|(t, _)| *t >= since).collect();
+
+  let slope = fit_slope(&recent);
+  let projected_exhaustion_at = slope
+    .filter(|slope| *slope > 0.0)
+    .map(|slope| last_at.saturating_add(((100.0 - current_percent).max(0.0) / slope) as u64));
+
+  let exhausts_before_reset = match (projected_exhaustion_at, reset_at) {
+    (Some(exhaustion), Some(reset)) => exhaustion < reset,
+    _ => false,
+  };
+
+  Some(Forecast {
+    current_percent,
+    rate_per_hour: slope.map(|slope| slope * 3_600_000.0),
+    projected_exhaustion_at,
+    reset_at,
+    exhausts_before_reset,
+    sample_count: recent.len(),
+  })
+}
+
+/// 将毫秒时长格式化为易读文本，如 "2 小时 5 分钟"、"40 分钟"
+pub fn format_duration_millis(millis: u64) -> String {
+  let total_minutes = millis / 60_000;
+  let hours = total_minutes / 60;
+  let minutes = total_minutes % 60;
+  if hours > 0 {
+    format!("{} 小时 {} 分钟", hours, minutes)
+  } else {
+    format!("{} 分钟", minutes)
+  }
+}
+
+/// 异常消耗（相邻两次读数之间的突增）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jump {
+  /// 使用量增量（百分点）
+  pub delta: f64,
+  /// 两次读数的时间间隔（毫秒）
+  pub span_millis: u64,
+  /// 消耗速度（百分点 / 分钟）
+  pub rate_per_minute: f64,
+}
+
+/// 计算相邻两次读数之间的消耗速度
+/// `previous` / `current` 为 (Unix 毫秒, 百分比)；时间间隔为 0 时返回 None
+pub fn consumption_between(previous: (u64, f64), current: (u64, f64)) -> Option<Jump> {
+  let span_millis = current.0.checked_sub(previous.0).filter(|span| *span > 0)?;
+  let delta = current.1 - previous.1;
+  Some(Jump {
+    delta,
+    span_millis,
+    rate_per_minute: delta / (span_millis as f64 / 60_000.0),
+  })
+}
+
+/// 读数：(Unix 毫秒, 百分比)
+type Sample = (u64, f64);
+
+/// 拆分出当前读数（最后一个）与窗口内（当前读数之前 `window_millis` 以内）的较早读数
+fn split_window(samples: &[Sample], window_millis: u64) -> Option<(Sample, &[Sample])> {
+  let (&current, earlier) = samples.split_last()?;
+  let since = current.0.saturating_sub(window_millis);
+  let start = earlier.partition_point(|(t, _)| *t < since);
+  Some((current, &earlier[start..]))
+}
+
+/// 最近一段时间内的上涨
+/// `samples` 为按时间升序排列的 (Unix 毫秒, 百分比)，最后一个为当前读数；
+/// 以窗口内最低的较早读数为基准，窗口内没有较早读数时返回 None
+pub fn rise_within(samples: &[(u64, f64)], window_millis: u64) -> Option<Jump> {
+  let (current, earlier) = split_window(samples, window_millis)?;
+  let baseline = earlier.iter().min_by(|a, b| a.1.total_cmp(&b.1))?;
+  consumption_between(*baseline, current)
+}
+
+/// 检测异常突增
+/// 与 rise_within 使用相同的窗口：当前读数相对窗口内任一较早读数的消耗速度超过 `limit_per_minute`
+/// 且增量不小于 MIN_JUMP_DELTA 时返回速度最快的一段；`limit_per_minute` 不大于 0 表示禁用
+pub fn detect_jump(samples: &[(u64, f64)], window_millis: u64, limit_per_minute: f64) -> Option<Jump> {
+  if limit_per_minute <= 0.0 {
+    return None;
+  }
+  let (current, earlier) = split_window(samples, window_millis)?;
+  earlier
+    .iter()
+    .filter_map(|baseline| consumption_between(*baseline, current))
+    .filter(|jump| jump.delta >= MIN_JUMP_DELTA && jump.rate_per_minute > limit_per_minute)
+    .max_by(|a, b| a.rate_per_minute.total_cmp(&b.rate_per_minute))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MINUTE: u64 = 60_000;
+  const HOUR: u64 = 60 * MINUTE;
+  const WINDOW: u64 = 3 * HOUR;
+
+  /// 从 `start` 开始每 `step` 毫秒一个读数
+  fn series(start: u64, step: u64, percents: &[f64]) -> Vec<(u64, f64)> {
+    percents.iter().enumerate().map(|(i, p)| (start + i as u64 * step, *p)).collect()
+  }
+
+  #[test]
+  fn no_samples_gives_no_forecast() {
+    assert!(forecast(&[], WINDOW, None).is_none());
+  }
+
+  #[test]
+  fn single_sample_has_no_rate() {
+    let result = forecast(&[(HOUR, 40.0)], WINDOW, None).unwrap();
+    assert_eq!(result.current_percent, 40.0);
+    assert_eq!(result.rate_per_hour, None);
+    assert_eq!(result.projected_exhaustion_at, None);
+    assert_eq!(result.sample_count, 1);
+  }
+
+  #[test]
+  fn short_span_has_no_rate() {
+    let samples = series(0, MINUTE, &[10.0, 20.0, 30.0]);
+    assert_eq!(forecast(&samples, WINDOW, None).unwrap().rate_per_hour, None);
+  }
+
+  #[test]
+  fn rising_series_projects_from_last_sample() {
+    // 每 30 分钟 +5 个百分点，即 10 / 小时；最后一个读数 60%，还需 4 小时
+    let samples = series(HOUR, 30 * MINUTE, &[40.0, 45.0, 50.0, 55.0, 60.0]);
+    let result = forecast(&samples, WINDOW, Some(HOUR * 100)).unwrap();
+    let last_at = samples.last().unwrap().0;
+    assert!((result.rate_per_hour.unwrap() - 10.0).abs() < 1e-9);
+    assert_eq!(result.projected_exhaustion_at, Some(last_at + 4 * HOUR));
+    assert!(result.exhausts_before_reset);
+  }
+
+
+  #[test]
+  fn window_is_measured_back_from_last_sample() {
+    let samples = series(0, HOUR, &[0.0, 10.0, 20.0, 30.0, 40.0, 50.0]);
+    assert_eq!(forecast(&samples, 2 * HOUR, None).unwrap().sample_count, 3);
+  }
+
+  #[test]
+  fn flat_series_never_exhausts() {
+    let samples = series(0, 30 * MINUTE, &[50.0, 50.0, 50.0, 50.0]);
+    let result = forecast(&samples, WINDOW, Some(HOUR)).unwrap();
+    assert_eq!(result.rate_per_hour, Some(0.0));
+    assert_eq!(result.projected_exhaustion_at, None);
+    assert!(!result.exhausts_before_reset);
+  }
+
+  #[test]
+  fn falling_series_never_exhausts() {
+    let samples = series(0, 30 * MINUTE, &[60.0, 55.0, 50.0, 45.0]);
+    let result = forecast(&samples, WINDOW, None).unwrap();
+    assert!(result.rate_per_hour.unwrap() < 0.0);
+    assert_eq!(result.projected_exhaustion_at, None);
+  }
+
+  #[test]
+  fn exhaustion_after_reset_is_not_flagged() {
+    let samples = series(0, 30 * MINUTE, &[40.0, 45.0, 50.0, 55.0, 60.0]);
+    let last_at = samples.last().unwrap().0;
+    let result = forecast(&samples, WINDOW, Some(last_at + HOUR)).unwrap();
+    assert!(!result.exhausts_before_reset);
+  }
+
+  #[test]
+  fn format_duration_uses_hours_when_needed() {
+    assert_eq!(format_duration_millis(40 * MINUTE), "40 分钟");
+    assert_eq!(format_duration_millis(2 * HOUR + 5 * MINUTE), "2 小时 5 分钟");
+  }
+
+  #[test]
+  fn rise_within_uses_lowest_reading_in_window() {
+    let samples = series(0, 5 * MINUTE, &[30.0, 10.0, 20.0, 40.0]);
+    // 窗口覆盖 5、10、15 分钟的读数，基准为 10%
+    let rise = rise_within(&samples, 10 * MINUTE).unwrap();
+    assert_eq!(rise.delta, 30.0);
+    assert_eq!(rise.span_millis, 10 * MINUTE);
+    // 窗口只覆盖上一次读数
+    assert_eq!(rise_within(&samples, 5 * MINUTE).unwrap().delta, 20.0);
+  }
+
+  #[test]
+  fn rise_within_needs_an_earlier_reading_in_window() {
+    assert!(rise_within(&[], 10 * MINUTE).is_none());
+    assert!(rise_within(&[(0, 10.0)], 10 * MINUTE).is_none());
+    assert!(rise_within(&series(0, 30 * MINUTE, &[10.0, 40.0]), 10 * MINUTE).is_none());
+  }
+
+  #[test]
+  fn jump_between_consecutive_readings() {
+    let samples = series(0, MINUTE, &[10.0, 15.0]);
+    let jump = detect_jump(&samples, 10 * MINUTE, 2.0).unwrap();
+    assert_eq!(jump.delta, 5.0);
+    assert_eq!(jump.rate_per_minute, 5.0);
+  }
+
+  #[test]
+  fn jump_spread_over_several_readings_is_detected() {
+    // 每 30 秒涨 1.5 个百分点：相邻读数的增量都不足 MIN_JUMP_DELTA，但窗口内每分钟涨 3 个百分点
+    let samples = series(0, MINUTE / 2, &[10.0, 11.5, 13.0, 14.5]);
+    assert!(detect_jump(&samples, MINUTE / 2, 2.0).is_none());
+    let jump = detect_jump(&samples, 10 * MINUTE, 2.0).unwrap();
+    assert_eq!(jump.rate_per_minute, 3.0);
+    assert!(jump.delta >= MIN_JUMP_DELTA);
+  }
+
+  #[test]
+  fn jump_picks_the_fastest_baseline() {
+    // 最低读数在窗口开头，但最近一分钟涨得最快
+    let samples = series(0, MINUTE, &[10.0, 10.0, 10.0, 20.0]);
+    let jump = detect_jump(&samples, 10 * MINUTE, 2.0).unwrap();
+    assert_eq!(jump.span_millis, MINUTE);
+    assert_eq!(jump.rate_per_minute, 10.0);
+  }
+
+  #[test]
+  fn jump_ignores_readings_outside_window() {
+    let samples = series(0, 20 * MINUTE, &[10.0, 60.0]);
+    assert!(detect_jump(&samples, 10 * MINUTE, 2.0).is_none());
+  }
+
+  #[test]
+  fn small_or_disabled_jumps_are_ignored() {
+    // 增量不足 MIN_JUMP_DELTA
+    assert!(detect_jump(&[(0, 10.0), (1_000, 11.0)], 10 * MINUTE, 2.0).is_none());
+    // 上限为 0 表示禁用
+    assert!(detect_jump(&series(0, MINUTE, &[10.0, 50.0]), 10 * MINUTE, 0.0).is_none());
+    // 使用量下降
+    assert!(detect_jump(&series(0, MINUTE, &[50.0, 10.0]), 10 * MINUTE, 2.0).is_none());
+  }
+}
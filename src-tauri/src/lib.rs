@@ -1,10 +1,30 @@
+mod alerts;
+mod bundle;
+mod forecast;
+mod migration;
+mod overrides;
+mod page_load;
+mod patch;
+mod paths;
+mod profiles;
+mod quota;
+mod rules;
+mod scheduler;
+mod schema;
+mod storage;
+#[cfg(test)]
+mod test_util;
+mod validation;
+
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tauri;
 use tauri::Manager;
 use tauri::Emitter;
+use tauri::Listener;
 use tokio::fs;
 use log::{info, warn, error};
+use chrono::Timelike;
 
 const MINMAX_USAGE_URL: &str = "https://platform.minimaxi.com/user-center/payment/coding-plan";
 const MINMAX_WINDOW_LABEL: &str = "minmax";
@@ -15,6 +35,21 @@ const MINMAX_INIT_SCRIPT: &str = r#"
   let lastSentPercent = null;
   let lastSentResetTime = null;
   let collectCount = 0;
+  let loginRequiredReported = false;
+
+  // 页面加载序号：后端刷新前写入 sessionStorage，新建窗口时由初始化脚本注入 __MINMAX_INITIAL_SEQ__
+  // 随每次上报一起发送，后端据此忽略刷新前旧页面发出的读数
+  const LOAD_SEQ = (function () {
+    let stored = NaN;
+    try {
+      stored = parseInt(sessionStorage.getItem('minmaxLoadSeq'), 10);
+    } catch (e) {
+      // sessionStorage 不可用时只使用初始序号
+    }
+    const initial = typeof window.__MINMAX_INITIAL_SEQ__ === 'number' ? window.__MINMAX_INITIAL_SEQ__ : NaN;
+    const candidates = [stored, initial].filter(Number.isFinite);
+    return candidates.length > 0 ? Math.max.apply(null, candidates) : null;
+  })();
   
   // 调试浮层元素
   let debugOverlay = null;
@@ -52,49 +87,22 @@ const MINMAX_INIT_SCRIPT: &str = r#"
 
   /**
    * 从页面文本中提取剩余重置时间
-   * 匹配格式: "X 小时 Y 分钟后重置", "X 天 Y 小时后重置", "X 分钟后重置" 等
+   * 匹配 "后重置" 之前由若干 "数字 + 单位" 组成的时长，如 "1 小时 26 分钟后重置"、"2 天 3 小时后重置"、"30 分钟后重置"
+   * 原样保留各个单位，由后端 quota::parse_reset_duration 换算
    * @param text 页面文本
-   * @returns 剩余时间字符串，如果未找到返回 null
+   * @returns 剩余时间字符串，如 "2 天 3 小时后"，如果未找到返回 null
    */
   function extractResetTimeFromText(text) {
     if (!text) return null;
 
-    // 匹配 "X 小时 Y 分钟后重置" 或类似格式
-    // 支持中文和数字，支持小数
-    const patterns = [
-      // "1 小时 26 分钟后重置" 或 "1小时26分钟后重置"
-      /(\d+(?:\.\d+)?)\s*(?:小时|钟头|h|hrs?)\s*(\d+(?:\.\d+)?)\s*(?:分钟|mins?|分钟|分)\s*(?:后)?\s*(?:后重置|后.*重置|重置)/i,
-      // "2 天 3 小时后重置"
-      /(\d+(?:\.\d+)?)\s*(?:天|日)\s*(\d+(?:\.\d+)?)\s*(?:小时|钟头|h|hrs?)\s*(?:后)?\s*(?:后重置|后.*重置|重置)/i,
-      // "30 分钟后重置" 或 "30分钟后重置"
-      /(\d+(?:\.\d+)?)\s*(?:分钟|mins?|分钟|分)\s*(?:后)?\s*(?:后重置|后.*重置|重置)/i,
-      // "X 小时后重置"
-      /(\d+(?:\.\d+)?)\s*(?:小时|钟头|h|hrs?)\s*(?:后)?\s*(?:后重置|后.*重置|重置)/i,
-    ];
-
-    for (const pattern of patterns) {
-      const match = text.match(pattern);
-      if (match) {
-        // 构建人类可读的时间字符串
-        let timeStr = '';
-        if (match.length >= 3 && match[2]) {
-          // 格式: "X 小时 Y 分钟"
-          timeStr = match[1] + ' 小时 ' + match[2] + ' 分钟后';
-        } else if (match.length >= 2) {
-          // 格式: "X 分钟" 或 "X 小时"
-          timeStr = match[1];
-          if (pattern.toString().includes('小时')) {
-            timeStr += ' 小时';
-          } else {
-            timeStr += ' 分钟后';
-          }
-        }
-        console.log(TAG, '提取到剩余时间:', timeStr);
-        return timeStr;
-      }
-    }
+    // 单位按长度优先排列，保证 "分钟" 优先于 "分"、"hrs" 优先于 "h"
+    const pattern = /((?:\d+(?:\.\d+)?\s*(?:天|日|小时|钟头|分钟|分|hrs?|h|mins?|d)\s*)+)(?:后)?\s*(?:后重置|后.*重置|重置)/i;
+    const match = text.match(pattern);
+    if (!match) return null;
 
-    return null;
+    const timeStr = match[1].replace(/\s+/g, ' ').trim() + '后';
+    console.log(TAG, '提取到剩余时间:', timeStr);
+    return timeStr;
   }
 
   /**
@@ -239,7 +247,7 @@ const MINMAX_INIT_SCRIPT: &str = r#"
       setTimeout(() => {
         const emitter = getTauriEventEmitter();
         if (emitter) {
-          emitter.emit('minmax-usage', { percent: percent });
+          emitter.emit('minmax-usage', { percent: percent, seq: LOAD_SEQ });
           console.log(TAG, '延迟上报使用量:', percent + '%');
         } else {
           console.warn(TAG, '延迟发送仍失败，Tauri API 不可用');
@@ -249,7 +257,7 @@ const MINMAX_INIT_SCRIPT: &str = r#"
     }
 
     try {
-      eventEmitter.emit('minmax-usage', { percent: percent });
+      eventEmitter.emit('minmax-usage', { percent: percent, seq: LOAD_SEQ });
       console.log(TAG, '上报使用量:', percent + '%');
       return true;
     } catch (e) {
@@ -258,6 +266,66 @@ const MINMAX_INIT_SCRIPT: &str = r#"
     }
   }
 
+  /**
+   * 上报提取失败事件
+   * @param message 失败原因
+   */
+  function emitExtractionError(message) {
+    const eventEmitter = getTauriEventEmitter();
+    if (!eventEmitter) return false;
+
+    try {
+      eventEmitter.emit('minmax-usage', { error: message, seq: LOAD_SEQ });
+      console.warn(TAG, '上报提取失败:', message);
+      return true;
+    } catch (e) {
+      console.error(TAG, '上报提取失败事件失败:', e);
+      return false;
+    }
+  }
+
+  /**
+   * 检测当前页面是否为 MiniMax 登录页（登录已失效）
+   * 依据：MiniMax 域名下的登录路由，或带有登录按钮的登录表单；
+   * 页面中普通的密码输入框或"请登录"提示文案不作为依据，避免误报
+   * @returns 是否需要重新登录
+   */
+  function detectLoginRequired() {
+    const location = window.location || {};
+    const hostname = location.hostname || '';
+    const pathname = location.pathname || '';
+    if (/(^|\.)minimaxi\.com$/i.test(hostname) && /^\/(user\/)?login(\/|$)/i.test(pathname)) return true;
+
+    const forms = document.querySelectorAll('form');
+    for (let i = 0; i < forms.length; i++) {
+      const form = forms[i];
+      if (!form.querySelector('input[type="password"]')) continue;
+      const buttons = form.querySelectorAll('button, input[type="submit"]');
+      for (let j = 0; j < buttons.length; j++) {
+        const label = (buttons[j].innerText || buttons[j].value || '').replace(/\s+/g, '');
+        if (label === '登录' || label === '立即登录') return true;
+      }
+    }
+    return false;
+  }
+
+  /**
+   * 上报登录失效事件（每个页面生命周期只上报一次）
+   */
+  function emitLoginRequired() {
+    if (loginRequiredReported) return;
+    const eventEmitter = getTauriEventEmitter();
+    if (!eventEmitter) return;
+
+    try {
+      eventEmitter.emit('minmax-login-required', { url: window.location.href, seq: LOAD_SEQ });
+      loginRequiredReported = true;
+      console.warn(TAG, '检测到登录已失效，已上报');
+    } catch (e) {
+      console.error(TAG, '上报登录失效失败:', e);
+    }
+  }
+
   function tryCollect() {
     if (!document || !document.body) return;
     collectCount++;
@@ -275,11 +343,16 @@ const MINMAX_INIT_SCRIPT: &str = r#"
       }
     }
 
-    if (!percent) {
+    if (percent === null) {
       // 调试：打印页面内容片段
       if (collectCount <= 3) {
         console.log(TAG, '检测 #' + collectCount + ': 未找到使用量数据，页面文本片段:', text.substring(0, 200));
       }
+      if (detectLoginRequired()) {
+        createOrUpdateOverlay('Login required', null, getTauriEventEmitter() ? 'Ready' : 'Missing');
+        emitLoginRequired();
+        return;
+      }
       createOrUpdateOverlay('Scanning...', null, getTauriEventEmitter() ? 'Ready' : 'Missing');
       return;
     }
@@ -398,77 +471,410 @@ const MINMAX_INIT_SCRIPT: &str = r#"
     setTimeout(tryCollect, 1000);
     setTimeout(tryCollect, 3000);
     setTimeout(tryCollect, 5000);
+    // 多次尝试后仍未找到数据，上报提取失败，便于后端及时结束等待
+    setTimeout(function () {
+      // 登录页已上报登录失效，不再重复上报提取失败
+      if (lastSentPercent === null && !loginRequiredReported) {
+        emitExtractionError('页面加载完成但未找到使用量数据');
+      }
+    }, 8000);
   });
 })();
 "#;
 
 /// 应用配置数据结构
-/// 用于存储用户的设置选项
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+/// 用于存储用户的设置选项；缺少的字段使用默认值，旧版配置在加载时由 migration 模块升级
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, Debug)]
+#[serde(default)]
 pub struct AppConfig {
-  /// 预警阈值（百分比）
-  #[serde(rename = "warningThreshold")]
-  pub warning_threshold: f64,
+  /// 配置版本
+  #[serde(rename = "version")]
+  pub version: u32,
+  /// 多级预警阈值（替代旧版的单一 warningThreshold）
+  #[serde(rename = "thresholdTiers", default = "alerts::default_tiers")]
+  pub threshold_tiers: Vec<alerts::ThresholdTier>,
   /// 检查间隔（分钟）
   #[serde(rename = "checkInterval")]
+  #[schemars(range(min = validation::MIN_CHECK_INTERVAL_MINUTES, max = validation::MAX_CHECK_INTERVAL_MINUTES))]
   pub check_interval: i32,
   /// 企业微信 Webhook URL
   #[serde(rename = "wechatWorkWebhookUrl")]
   pub wechat_work_webhook_url: String,
   /// 语言设置
   #[serde(rename = "language")]
+  #[schemars(extend("enum" = validation::SUPPORTED_LANGUAGES))]
   pub language: String,
+  /// 连续失败多少次后发送错误通知
+  #[serde(rename = "failureNotifyAfter", default = "default_failure_notify_after")]
+  pub failure_notify_after: u32,
+  /// 超过多少分钟没有获取到新数据时告警（0 表示禁用）
+  #[serde(rename = "staleAfterMinutes", default = "default_stale_after_minutes")]
+  pub stale_after_minutes: u32,
+  /// 检测到登录失效时是否自动显示 MiniMax 窗口
+  #[serde(rename = "openWindowOnLoginRequired", default)]
+  pub open_window_on_login_required: bool,
+  /// 页面加载超时时间（秒），超时后重建 MiniMax 窗口
+  #[serde(rename = "pageLoadTimeoutSecs", default = "default_page_load_timeout_secs")]
+  #[schemars(range(min = 1))]
+  pub page_load_timeout_secs: u32,
+  /// 隐藏窗口定期重建周期（小时，0 表示禁用）
+  #[serde(rename = "webviewRecycleHours", default = "default_webview_recycle_hours")]
+  pub webview_recycle_hours: u32,
+  /// 休眠唤醒或系统时间跳变后的补偿策略
+  #[serde(rename = "catchUpPolicy", default)]
+  pub catch_up_policy: scheduler::CatchUpPolicy,
+  /// 告警回差（百分点）：使用量回落到阈值减该值以下后才重新布防
+  #[serde(rename = "alertHysteresis", default = "default_alert_hysteresis")]
+  #[schemars(range(min = 0.0, max = 100.0))]
+  pub alert_hysteresis: f64,
+  /// 持续超过阈值时的重复通知冷却时间（分钟，0 表示不重复通知）
+  #[serde(rename = "alertCooldownMinutes", default)]
+  pub alert_cooldown_minutes: u32,
+  /// 检测到配额重置时是否通知
+  #[serde(rename = "notifyQuotaReset", default = "default_true")]
+  pub notify_quota_reset: bool,
+  /// 使用量回落到阈值以下时是否发送恢复通知
+  #[serde(rename = "notifyRecovery", default = "default_true")]
+  pub notify_recovery: bool,
+  /// 预计在重置前用满时是否告警
+  #[serde(rename = "forecastAlert", default = "default_true")]
+  pub forecast_alert: bool,
+  /// 趋势拟合使用的时间窗口（分钟）
+  #[serde(rename = "forecastWindowMinutes", default = "default_forecast_window_minutes")]
+  #[schemars(range(min = 1))]
+  pub forecast_window_minutes: u32,
+  /// 配额重置前多少分钟提醒（0 表示禁用）
+  #[serde(rename = "preResetReminderMinutes", default)]
+  pub pre_reset_reminder_minutes: u32,
+  /// 配额重置前多少分钟检查是否有大量未用配额（0 表示禁用）
+  #[serde(rename = "unusedQuotaNudgeMinutes", default)]
+  pub unused_quota_nudge_minutes: u32,
+  /// 使用量低于该值（百分比）时视为配额未充分使用
+  #[serde(rename = "unusedQuotaNudgePercent", default = "default_unused_quota_nudge_percent")]
+  #[schemars(range(min = 0.0, max = 100.0))]
+  pub unused_quota_nudge_percent: f64,
+  /// 严重告警未确认时的重复提醒间隔（分钟，0 表示不重复）
+  #[serde(rename = "criticalRepeatMinutes", default = "default_critical_repeat_minutes")]
+  pub critical_repeat_minutes: u32,
+  /// 严重告警的升级步骤：第一组对应首次告警，第 n 次重复提醒使用第 n + 1 组渠道，超出后沿用最后一组；
+  /// 只支持系统通知与企业微信，不支持邮件
+  #[serde(rename = "criticalEscalation", default = "alerts::default_escalation_steps")]
+  pub critical_escalation: Vec<Vec<alerts::NotificationChannel>>,
+  /// 自定义告警规则，条件语法见 rules 模块
+  #[serde(rename = "alertRules", default)]
+  pub alert_rules: Vec<rules::AlertRule>,
+  /// 最近一段时间内的消耗速度超过该值（百分点 / 分钟）时发送异常消耗告警（0 表示禁用）
+  #[serde(rename = "anomalyRatePerMinute", default = "default_anomaly_rate_per_minute")]
+  #[schemars(range(min = 0.0))]
+  pub anomaly_rate_per_minute: f64,
+  /// 异常消耗检测回看的时间窗口（分钟），当前读数与窗口内任一较早读数比较
+  #[serde(rename = "anomalyWindowMinutes", default = "default_anomaly_window_minutes")]
+  #[schemars(range(min = 1))]
+  pub anomaly_window_minutes: u32,
+  /// 异常消耗告警的级别，决定通知标题、通知渠道（该级别阈值配置的渠道）与是否受静音影响
+  #[serde(rename = "anomalySeverity", default = "default_anomaly_severity")]
+  pub anomaly_severity: alerts::Severity,
+  /// 当前生效的配置方案名称，未使用方案时为空
+  #[serde(rename = "activeProfile", default)]
+  pub active_profile: Option<String>,
+}
+
+/// 默认连续失败 3 次后发送错误通知
+fn default_failure_notify_after() -> u32 {
+  3
+}
+
+/// 默认 120 分钟没有新数据时告警
+fn default_stale_after_minutes() -> u32 {
+  120
+}
+
+/// 默认页面加载超时 60 秒
+fn default_page_load_timeout_secs() -> u32 {
+  60
+}
+
+/// 默认每 12 小时重建一次隐藏窗口
+fn default_webview_recycle_hours() -> u32 {
+  12
+}
+
+/// 布尔开关默认开启
+fn default_true() -> bool {
+  true
+}
+
+/// 默认回差 5 个百分点
+fn default_alert_hysteresis() -> f64 {
+  5.0
+}
+
+/// 默认使用最近 120 分钟的读数做趋势拟合
+fn default_forecast_window_minutes() -> u32 {
+  120
+}
+
+/// 默认使用量低于 50% 时提醒配额未充分使用
+fn default_unused_quota_nudge_percent() -> f64 {
+  50.0
+}
+
+/// 默认每 15 分钟重复一次严重告警
+fn default_critical_repeat_minutes() -> u32 {
+  15
+}
+
+/// 默认每分钟消耗超过 2 个百分点视为异常
+fn default_anomaly_rate_per_minute() -> f64 {
+  2.0
+}
+
+/// 默认回看最近 10 分钟的读数
+fn default_anomaly_window_minutes() -> u32 {
+  10
+}
+
+/// 异常消耗告警默认为预警级别
+fn default_anomaly_severity() -> alerts::Severity {
+  alerts::Severity::Warning
 }
 
 impl Default for AppConfig {
   fn default() -> Self {
     Self {
-      warning_threshold: 90.0,
+      version: migration::CURRENT_CONFIG_VERSION,
+      threshold_tiers: alerts::default_tiers(),
       check_interval: 30,
       wechat_work_webhook_url: String::new(),
       language: "zh".to_string(),
+      failure_notify_after: default_failure_notify_after(),
+      stale_after_minutes: default_stale_after_minutes(),
+      open_window_on_login_required: false,
+      page_load_timeout_secs: default_page_load_timeout_secs(),
+      webview_recycle_hours: default_webview_recycle_hours(),
+      catch_up_policy: scheduler::CatchUpPolicy::default(),
+      alert_hysteresis: default_alert_hysteresis(),
+      alert_cooldown_minutes: 0,
+      notify_quota_reset: true,
+      notify_recovery: true,
+      forecast_alert: true,
+      forecast_window_minutes: default_forecast_window_minutes(),
+      pre_reset_reminder_minutes: 0,
+      unused_quota_nudge_minutes: 0,
+      unused_quota_nudge_percent: default_unused_quota_nudge_percent(),
+      critical_repeat_minutes: default_critical_repeat_minutes(),
+      critical_escalation: alerts::default_escalation_steps(),
+      alert_rules: Vec::new(),
+      anomaly_rate_per_minute: default_anomaly_rate_per_minute(),
+      anomaly_window_minutes: default_anomaly_window_minutes(),
+      anomaly_severity: default_anomaly_severity(),
+      active_profile: None,
     }
   }
 }
 
 /// 获取配置文件路径
-/// 返回应用数据目录下的 config.json 路径
-fn get_config_path() -> std::path::PathBuf {
-  // 使用标准方式获取应用数据目录
-  // Tauri 2.x 简化处理：使用 HOME 目录下的隐藏文件夹
-  let home_dir = std::env::var("HOME")
-    .or_else(|_| std::env::var("USERPROFILE"))
-    .expect("无法获取用户主目录");
+/// 目录按命令行参数、环境变量、便携模式、XDG 规范的顺序确定，详见 paths 模块
+fn get_config_path() -> Result<std::path::PathBuf, String> {
+  Ok(paths::app_paths()?.config_file())
+}
+
+/// 获取告警状态文件路径
+/// 位于状态目录下的 alert_state.json
+fn get_alert_state_path() -> Result<std::path::PathBuf, String> {
+  Ok(paths::app_paths()?.alert_state_file())
+}
+
+/// 加载告警状态
+/// 文件不存在或解析失败时返回空状态（所有级别处于布防状态）
+async fn load_alert_state() -> alerts::AlertState {
+  let path = match get_alert_state_path() {
+    Ok(path) => path,
+    Err(e) => {
+      warn!("无法确定告警状态文件路径，使用空状态: {}", e);
+      return alerts::AlertState::default();
+    }
+  };
+  match fs::read_to_string(&path).await {
+    Ok(content) => match serde_json::from_str(&content) {
+      Ok(state) => state,
+      Err(e) => {
+        warn!("告警状态解析失败，使用空状态: {}", e);
+        alerts::AlertState::default()
+      }
+    },
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => alerts::AlertState::default(),
+    Err(e) => {
+      warn!("读取告警状态失败，使用空状态: {}", e);
+      alerts::AlertState::default()
+    }
+  }
+}
+
+/// 保存告警状态
+async fn save_alert_state(state: &alerts::AlertState) {
+  let path = match get_alert_state_path() {
+    Ok(path) => path,
+    Err(e) => {
+      error!("无法确定告警状态文件路径: {}", e);
+      return;
+    }
+  };
+  match serde_json::to_string_pretty(state) {
+    Ok(content) => {
+      // 原子写入，崩溃时不会留下截断的文件导致所有级别重新告警
+      let result = run_blocking(move || {
+        storage::write_atomic(&path, content.as_bytes()).map_err(|e| e.to_string())
+      })
+      .await;
+      if let Err(e) = result {
+        error!("告警状态写入失败: {}", e);
+      }
+    }
+    Err(e) => error!("告警状态序列化失败: {}", e),
+  }
+}
+
+/// 解析配置文件内容，并按版本逐步迁移到当前版本
+fn parse_config(content: &str) -> Result<(AppConfig, migration::MigrationOutcome), String> {
+  let mut value: serde_json::Value = serde_json::from_str(content)
+    .map_err(|e| format!("配置解析失败: {}", e))?;
+  let outcome = migration::migrate_config(&mut value)?;
+  let config: AppConfig = serde_json::from_value(value)
+    .map_err(|e| format!("配置解析失败: {}", e))?;
+  Ok((config, outcome))
+}
+
+/// 修正配置文件中无效的字段（见 validation::sanitize），并记录被修正的字段
+fn sanitize_loaded(config: AppConfig) -> AppConfig {
+  let (config, errors) = validation::sanitize(config);
+  for e in &errors {
+    warn!("配置字段 {} 无效（{}），已忽略文件中的取值", e.field, e.message);
+  }
+  config
+}
+
+/// 写入配置文件，并把写入的内容记录到配置文件的变更检测中，配置文件监听据此忽略本应用自己的写入
+async fn write_config_file(app_state: &Arc<AppState>, config: &AppConfig) -> Result<(), String> {
+  let content = write_config_contents(config).await?;
+  app_state.config_file().record_write(Some(content));
+  Ok(())
+}
+
+/// 原子写入配置文件，返回写入的内容
+/// 持有配置文件锁，写入前轮转备份旧文件；被环境变量或命令行参数覆盖的字段保留文件中原有的取值；
+/// 现有文件由更新版本的程序写入时拒绝覆盖
+async fn write_config_contents(config: &AppConfig) -> Result<Vec<u8>, String> {
+  let app_paths = paths::app_paths()?;
+  let (config_path, backup_dir) = (app_paths.config_file(), app_paths.backup_dir());
+  let value = serde_json::to_value(config).map_err(|e| format!("配置序列化失败: {}", e))?;
+  run_blocking(move || {
+    storage::save_config(&config_path, &backup_dir, now_millis(), storage::BACKUP_KEEP, |existing| {
+      render_config_file(value, existing).map_err(std::io::Error::other)
+    })
+    .map_err(|e| format!("配置写入失败: {}", e))
+  })
+  .await
+}
+
+/// 当前配置在配置文件层面的取值：去掉环境变量与命令行参数的覆盖，被覆盖的字段取配置文件（迁移后）中的值
+/// 调用方应持有配置锁，保证读到的文件与 `config` 对应
+async fn file_level_config(config: &AppConfig) -> Result<AppConfig, String> {
+  let file = match fs::read_to_string(get_config_path()?).await {
+    Ok(content) => {
+      let mut value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("配置解析失败: {}", e))?;
+      migration::migrate_config(&mut value)?;
+      match value {
+        serde_json::Value::Object(fields) => Some(fields),
+        _ => None,
+      }
+    }
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+    Err(e) => return Err(format!("读取配置文件失败: {}", e)),
+  };
+  overrides::file_config(config, file.as_ref(), overrides::overrides())
+}
 
-  let app_data_dir = std::path::PathBuf::from(home_dir)
-    .join(".minmax-helper");
+/// 根据现有配置文件生成要写入的内容
+fn render_config_file(mut value: serde_json::Value, existing: Option<&[u8]>) -> Result<Vec<u8>, String> {
+  let file: Option<serde_json::Value> = existing.and_then(|content| serde_json::from_slice(content).ok());
+  if let Some(file) = &file {
+    migration::ensure_writable(file)?;
+  }
+  let overrides = overrides::overrides();
+  if let serde_json::Value::Object(settings) = &mut value {
+    overrides::restore_file_values(settings, file.as_ref().and_then(|file| file.as_object()), overrides);
+  }
+  serde_json::to_vec_pretty(&value).map_err(|e| format!("配置序列化失败: {}", e))
+}
 
-  // 确保目录存在
-  std::fs::create_dir_all(&app_data_dir).expect("无法创建应用数据目录");
+/// 应用新的配置
+/// 调用方持有配置锁 `state`：修改配置的命令从读取当前配置、合并、校验、写入文件到更新状态都在同一次加锁内完成，
+/// 避免并发的修改互相覆盖。叠加环境变量与命令行参数的覆盖后更新应用状态，重新编译自定义规则，
+/// 通知定时任务按新的间隔重新调度，并向前端发送 settings-changed 事件；返回生效的配置
+async fn apply_config_locked(
+  app: &tauri::AppHandle,
+  app_state: &Arc<AppState>,
+  state: &mut AppConfig,
+  config: AppConfig,
+) -> AppConfig {
+  let config = overrides::apply(config);
+  *state = config.clone();
+  compile_rules(app_state, &config).await;
+  app_state.reschedule.notify_one();
+  if let Err(e) = app.emit("settings-changed", &config) {
+    warn!("发送 settings-changed 事件失败: {}", e);
+  }
+  config
+}
 
-  app_data_dir.join("config.json")
+/// 按配置解析自定义规则的条件，配置生效时调用一次，之后每次读数只求值
+async fn compile_rules(app_state: &Arc<AppState>, config: &AppConfig) {
+  let mut rule_state = app_state.rules.lock().await;
+  rule_state.set_rules(&config.alert_rules);
 }
 
 /// 加载应用配置
-/// 从 JSON 文件中读取配置，如果文件不存在则返回默认配置
+/// 从 JSON 文件中读取配置，如果文件不存在则返回默认配置；文件中无效的字段被修正，返回值已叠加环境变量与命令行参数的覆盖
 #[tauri::command]
 async fn get_settings() -> Result<AppConfig, String> {
-  let config_path = get_config_path();
+  let config_path = get_config_path()?;
   info!("加载配置，路径: {:?}", config_path);
 
   // 尝试读取配置文件
   match fs::read_to_string(&config_path).await {
     Ok(content) => {
-      // 解析 JSON 配置
-      let config: AppConfig = serde_json::from_str(&content)
-        .map_err(|e| format!("配置解析失败: {}", e))?;
+      let (config, outcome) = parse_config(&content)?;
+      let config = sanitize_loaded(config);
+
+      if outcome.migrated() {
+        info!(
+          "配置已从第 {} 版迁移到第 {} 版，备份原文件并写回",
+          outcome.from_version, outcome.to_version
+        );
+        // 原子写入备份；同一版本的备份已存在时（上次写回失败）保留最早的原文件
+        let backup_path = config_path.with_file_name(format!("config.v{}.bak.json", outcome.from_version));
+        let backup = run_blocking(move || {
+          storage::write_new_atomic(&backup_path, content.as_bytes()).map_err(|e| e.to_string())
+        })
+        .await;
+        if let Err(e) = backup {
+          warn!("备份迁移前的配置失败: {}", e);
+        } else if let Err(e) = write_config_contents(&config).await {
+          warn!("写回迁移后的配置失败: {}", e);
+        }
+        // 迁移后的文件与 Schema 一起更新，编辑器打开新文件时按当前版本校验
+        write_settings_schema().await;
+      }
+
       info!("配置加载成功: {:?}", config);
-      Ok(config)
+      Ok(overrides::apply(config))
     }
     Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
       // 文件不存在，返回默认配置
       info!("配置文件不存在，使用默认配置");
-      Ok(AppConfig::default())
+      Ok(overrides::apply(AppConfig::default()))
     }
     Err(e) => {
       // 其他读取错误，返回错误信息
@@ -479,57 +885,373 @@ async fn get_settings() -> Result<AppConfig, String> {
   }
 }
 
+/// 获取生效的配置及各字段的来源
+/// 来源依次为命令行参数、环境变量、配置文件与默认值，便于排查受管机器上的配置
+#[tauri::command]
+async fn get_effective_settings() -> Result<overrides::EffectiveSettings, String> {
+  let (config, fields) = match fs::read_to_string(get_config_path()?).await {
+    Ok(content) => {
+      let mut value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("配置解析失败: {}", e))?;
+      migration::migrate_config(&mut value)?;
+      let config: AppConfig = serde_json::from_value(value.clone())
+        .map_err(|e| format!("配置解析失败: {}", e))?;
+      let config = sanitize_loaded(config);
+      let fields = match value {
+        serde_json::Value::Object(fields) => fields,
+        _ => serde_json::Map::new(),
+      };
+      (config, fields)
+    }
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => (AppConfig::default(), serde_json::Map::new()),
+    Err(e) => return Err(format!("读取配置文件失败: {}", e)),
+  };
+
+  Ok(overrides::effective(config, &fields, overrides::overrides()))
+}
+
+/// 获取配置的 JSON Schema
+#[tauri::command]
+async fn get_settings_schema() -> serde_json::Value {
+  schema::settings_schema()
+}
+
+/// 把配置的 JSON Schema 写入配置目录，供编辑器与部署脚本校验 config.json
+async fn write_settings_schema() {
+  let result = match paths::app_paths() {
+    Ok(app_paths) => {
+      let path = app_paths.schema_file();
+      run_blocking(move || schema::write_schema_file(&path)).await
+    }
+    Err(e) => Err(e),
+  };
+  match result {
+    Ok(true) => info!("配置 Schema 已更新"),
+    Ok(false) => {}
+    Err(e) => warn!("写入配置 Schema 失败: {}", e),
+  }
+}
+
+/// 配置保存的错误类型
+/// 序列化为 { kind, ... }，校验失败时附带按字段区分的错误，前端可映射到对应表单项
+#[derive(serde::Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum SettingsError {
+  /// 配置校验失败
+  Invalid { errors: Vec<validation::FieldError> },
+  /// 配置序列化或读写失败
+  Storage { message: String },
+}
+
+impl From<String> for SettingsError {
+  fn from(message: String) -> Self {
+    SettingsError::Storage { message }
+  }
+}
+
 /// 保存应用配置
-/// 将配置保存到 JSON 文件
+/// 校验通过后将配置原子写入 JSON 文件；写入与更新应用状态期间一直持有配置锁
 #[tauri::command]
-async fn save_settings(settings: AppConfig) -> Result<(), String> {
-  let config_path = get_config_path();
+async fn save_settings(
+  app: tauri::AppHandle,
+  app_state: tauri::State<'_, Arc<AppState>>,
+  settings: AppConfig,
+) -> Result<(), SettingsError> {
+  let config_path = get_config_path()?;
   info!("保存配置，路径: {:?}, 内容: {:?}", config_path, settings);
 
-  // 序列化配置为 JSON
-  let content = serde_json::to_string_pretty(&settings)
-    .map_err(|e| format!("配置序列化失败: {}", e))?;
-
-  // 写入文件
-  fs::write(&config_path, content)
-    .await
-    .map_err(|e| format!("配置写入失败: {}", e))?;
+  if let Err(errors) = validation::validate_config(&settings) {
+    warn!("配置校验失败: {:?}", errors);
+    return Err(SettingsError::Invalid { errors });
+  }
+  let settings = AppConfig {
+    version: migration::CURRENT_CONFIG_VERSION,
+    ..settings
+  };
 
+  // 同步到应用状态，后端告警评估与定时任务使用最新配置
+  let mut state = app_state.config.lock().await;
+  write_config_file(app_state.inner(), &settings).await?;
+  apply_config_locked(&app, app_state.inner(), &mut state, settings).await;
   info!("配置保存成功");
 
-  // 验证：立即读取并返回配置内容用于调试
-  match fs::read_to_string(&config_path).await {
-    Ok(content) => {
-      info!("配置文件内容: {}", content);
-    }
-    Err(e) => {
-      error!("验证读取配置文件失败: {}", e);
-    }
+  Ok(())
+}
+
+/// 局部更新配置
+/// 把 JSON Merge Patch 合并到当前配置，校验通过后写入文件并立即生效；补丁中含有未知字段时返回校验错误；
+/// 合并、写入与更新应用状态期间一直持有配置锁，避免与其他修改交错
+/// 被删除（设为 null）的字段恢复为默认值
+#[tauri::command]
+async fn patch_settings(
+  app: tauri::AppHandle,
+  app_state: tauri::State<'_, Arc<AppState>>,
+  patch: serde_json::Value,
+) -> Result<AppConfig, SettingsError> {
+  let Some(fields) = patch.as_object() else {
+    return Err("配置补丁必须是 JSON 对象".to_string().into());
+  };
+  info!("局部更新配置: {}", patch);
+  if let Err(errors) = patch::check_fields(fields) {
+    warn!("配置补丁包含未知字段: {:?}", errors);
+    return Err(SettingsError::Invalid { errors });
   }
 
-  Ok(())
+  let mut state = app_state.config.lock().await;
+  let mut merged = serde_json::to_value(&*state).map_err(|e| format!("配置序列化失败: {}", e))?;
+  patch::merge_patch(&mut merged, &patch);
+  let config: AppConfig = serde_json::from_value(merged).map_err(|e| format!("配置补丁无效: {}", e))?;
+  let config = AppConfig {
+    version: migration::CURRENT_CONFIG_VERSION,
+    ..config
+  };
+  if let Err(errors) = validation::validate_config(&config) {
+    warn!("配置补丁校验失败: {:?}", errors);
+    return Err(SettingsError::Invalid { errors });
+  }
+
+  write_config_file(app_state.inner(), &config).await?;
+  let config = apply_config_locked(&app, app_state.inner(), &mut state, config).await;
+
+  info!("配置局部更新成功");
+  Ok(config)
 }
 
 /// 恢复出厂设置
-/// 删除配置文件，后续读取会回退到默认配置
+/// 备份并删除配置文件，后续读取会回退到默认配置
 #[tauri::command]
-async fn reset_settings() -> Result<AppConfig, String> {
-  let config_path = get_config_path();
+async fn reset_settings(app: tauri::AppHandle, app_state: tauri::State<'_, Arc<AppState>>) -> Result<AppConfig, String> {
+  let app_paths = paths::app_paths()?;
+  let (config_path, backup_dir) = (app_paths.config_file(), app_paths.backup_dir());
   info!("恢复出厂设置，准备删除配置文件: {:?}", config_path);
 
-  match fs::remove_file(&config_path).await {
-    Ok(_) => info!("配置文件已删除，恢复默认配置"),
-    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-      info!("配置文件不存在，直接返回默认配置")
+  let mut state = app_state.config.lock().await;
+  let result = tokio::task::spawn_blocking(move || {
+    storage::remove_config(&config_path, &backup_dir, now_millis(), storage::BACKUP_KEEP)
+  })
+  .await
+  .map_err(|e| format!("删除配置文件任务失败: {}", e))?;
+  if let Err(e) = result {
+    let error_msg = format!("删除配置文件失败: {}", e);
+    error!("{}", error_msg);
+    return Err(error_msg);
+  }
+  app_state.config_file().record_write(None);
+  info!("配置文件已删除，恢复默认配置");
+
+  Ok(apply_config_locked(&app, app_state.inner(), &mut state, AppConfig::default()).await)
+}
+
+/// 列出配置备份
+/// 每次保存或恢复出厂设置前都会备份旧的配置文件，按时间从新到旧排列
+#[tauri::command]
+async fn list_config_backups() -> Result<Vec<storage::BackupInfo>, String> {
+  let dir = paths::app_paths()?.backup_dir();
+  tokio::task::spawn_blocking(move || storage::list_backups(&dir))
+    .await
+    .map_err(|e| format!("读取配置备份任务失败: {}", e))?
+    .map_err(|e| format!("读取配置备份失败: {}", e))
+}
+
+/// 从备份恢复配置
+/// 备份内容需通过校验；恢复前会先备份当前配置，返回恢复后的配置
+#[tauri::command]
+async fn restore_config_backup(
+  app: tauri::AppHandle,
+  app_state: tauri::State<'_, Arc<AppState>>,
+  name: String,
+) -> Result<AppConfig, SettingsError> {
+  let dir = paths::app_paths()?.backup_dir();
+  let path = storage::backup_path(&dir, &name).ok_or_else(|| format!("无效的备份名称: {}", name))?;
+  info!("从备份恢复配置: {:?}", path);
+
+  let content = fs::read_to_string(&path)
+    .await
+    .map_err(|e| format!("读取配置备份失败: {}", e))?;
+  let (config, _) = parse_config(&content)?;
+  if let Err(errors) = validation::validate_config(&config) {
+    warn!("备份中的配置校验失败: {:?}", errors);
+    return Err(SettingsError::Invalid { errors });
+  }
+
+  let mut state = app_state.config.lock().await;
+  write_config_file(app_state.inner(), &config).await?;
+  let config = apply_config_locked(&app, app_state.inner(), &mut state, config).await;
+
+  info!("配置已从备份 {} 恢复", name);
+  Ok(config)
+}
+
+/// 导出配置包
+/// 返回配置包 JSON，由前端交给用户选择保存位置；`include_secrets` 为 false 时不包含 Webhook 等密钥。
+/// 不接受文件路径：webview 中的脚本不能借此向任意位置写入文件
+#[tauri::command]
+async fn export_settings(
+  app_state: tauri::State<'_, Arc<AppState>>,
+  include_secrets: bool,
+) -> Result<String, String> {
+  let config = {
+    let state = app_state.config.lock().await;
+    state.clone()
+  };
+  let bundle = bundle::export_bundle(&config, include_secrets, now_millis())?;
+  let content = serde_json::to_string_pretty(&bundle)
+    .map_err(|e| format!("配置包序列化失败: {}", e))?;
+  info!("导出配置包（包含密钥: {}）", include_secrets);
+
+  Ok(content)
+}
+
+/// 导入配置包
+/// 解析前端从用户选择的文件中读取的配置包内容 `content`，合并到当前配置后保存；
+/// 默认保留本机的 Webhook 等密钥，`overwrite_secrets` 为 true 时使用配置包中的密钥。
+/// 不接受文件路径：webview 中的脚本不能借此读取任意文件
+#[tauri::command]
+async fn import_settings(
+  app: tauri::AppHandle,
+  app_state: tauri::State<'_, Arc<AppState>>,
+  content: String,
+  overwrite_secrets: Option<bool>,
+) -> Result<AppConfig, SettingsError> {
+  info!("导入配置包");
+  let preset = bundle::parse_bundle(&content)?;
+
+  let mut state = app_state.config.lock().await;
+  let config = bundle::merge_bundle(&state, preset, overwrite_secrets.unwrap_or(false))?;
+  if let Err(errors) = validation::validate_config(&config) {
+    warn!("导入的配置校验失败: {:?}", errors);
+    return Err(SettingsError::Invalid { errors });
+  }
+
+  write_config_file(app_state.inner(), &config).await?;
+  let config = apply_config_locked(&app, app_state.inner(), &mut state, config).await;
+
+  info!("配置包导入成功");
+  Ok(config)
+}
+
+/// 在阻塞线程池中执行文件操作
+async fn run_blocking<T: Send + 'static>(
+  f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+  tokio::task::spawn_blocking(f)
+    .await
+    .map_err(|e| format!("文件操作任务失败: {}", e))?
+}
+
+/// 列出配置方案
+#[tauri::command]
+async fn list_profiles(app_state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<profiles::ProfileInfo>, String> {
+  let dir = paths::app_paths()?.profile_dir();
+  let names = run_blocking(move || profiles::list(&dir).map_err(|e| format!("读取配置方案失败: {}", e))).await?;
+  let active = app_state.config.lock().await.active_profile.clone();
+
+  Ok(
+    names
+      .into_iter()
+      .map(|name| profiles::ProfileInfo {
+        active: active.as_deref() == Some(name.as_str()),
+        name,
+      })
+      .collect(),
+  )
+}
+
+/// 创建配置方案
+/// 默认以当前配置为内容，`from_defaults` 为 true 时使用默认配置；方案不保存密钥，也不包含本机环境变量与命令行参数的覆盖；
+/// 同名方案已存在时返回错误
+#[tauri::command]
+async fn create_profile(
+  app_state: tauri::State<'_, Arc<AppState>>,
+  name: String,
+  from_defaults: Option<bool>,
+) -> Result<(), String> {
+  profiles::validate_name(&name)?;
+  let config = if from_defaults.unwrap_or(false) {
+    AppConfig::default()
+  } else {
+    let state = app_state.config.lock().await;
+    file_level_config(&state).await?
+  };
+  let content = profiles::to_content(&config)?;
+
+  let dir = paths::app_paths()?.profile_dir();
+  let profile_name = name.clone();
+  run_blocking(move || profiles::create(&dir, &profile_name, &content)).await?;
+
+  info!("[profiles] 已创建配置方案: {}", name);
+  Ok(())
+}
+
+/// 切换配置方案
+/// 先把当前配置保存回当前方案，再加载目标方案并立即生效：重新调度定时任务、重新编译自定义规则、
+/// 清理新方案中已不存在的阈值级别及其升级提醒，并按新方案评估最近一次读数；Webhook 等密钥沿用本机当前的取值。
+/// 写回与读取方案都使用配置文件层面的配置，本机的覆盖不会写进方案；写回原方案到切换完成期间一直持有配置锁
+#[tauri::command]
+async fn switch_profile(
+  app: tauri::AppHandle,
+  app_state: tauri::State<'_, Arc<AppState>>,
+  name: String,
+) -> Result<AppConfig, SettingsError> {
+  let dir = paths::app_paths()?.profile_dir();
+  let mut state = app_state.config.lock().await;
+  let current = file_level_config(&state).await?;
+
+  let profile_dir = dir.clone();
+  let profile_name = name.clone();
+  let content = run_blocking(move || profiles::read(&profile_dir, &profile_name)).await?;
+  let config = profiles::load(&content, &name, &current)?;
+  if let Err(errors) = validation::validate_config(&config) {
+    warn!("[profiles] 方案 {} 校验失败: {:?}", name, errors);
+    return Err(SettingsError::Invalid { errors });
+  }
+
+  // 保存当前方案的修改
+  if let Some(active) = current.active_profile.clone().filter(|active| active != &name) {
+    let content = profiles::to_content(&current)?;
+    if let Err(e) = run_blocking(move || profiles::write(&dir, &active, &content)).await {
+      warn!("[profiles] 保存当前方案失败: {}", e);
     }
-    Err(e) => {
-      let error_msg = format!("删除配置文件失败: {}", e);
-      error!("{}", error_msg);
-      return Err(error_msg);
+  }
+
+  write_config_file(app_state.inner(), &config).await?;
+  let config = apply_config_locked(&app, app_state.inner(), &mut state, config).await;
+  drop(state);
+  info!("[profiles] 已切换到配置方案: {}", name);
+
+  // 清理新方案中已不存在的阈值级别，避免旧方案的严重告警一直重复提醒
+  {
+    let mut alert_state = app_state.alerts.lock().await;
+    if alert_state.retain_tiers(&config.threshold_tiers) {
+      save_alert_state(&alert_state).await;
     }
   }
 
-  Ok(AppConfig::default())
+  // 按新方案的阈值与预测设置立即评估最近一次读数
+  let last_percent = app_state.last_reading.lock().await.as_ref().map(|r| r.percent);
+  if let Some(percent) = last_percent {
+    evaluate_usage_alerts(app_state.inner(), percent).await;
+    evaluate_forecast_alert(app_state.inner()).await;
+  }
+
+  Ok(config)
+}
+
+/// 删除配置方案
+/// 不能删除当前生效的方案
+#[tauri::command]
+async fn delete_profile(app_state: tauri::State<'_, Arc<AppState>>, name: String) -> Result<(), String> {
+  let active = app_state.config.lock().await.active_profile.clone();
+  if active.as_deref() == Some(name.as_str()) {
+    return Err(format!("不能删除当前生效的方案: {}", name));
+  }
+
+  let dir = paths::app_paths()?.profile_dir();
+  let profile_name = name.clone();
+  run_blocking(move || profiles::delete(&dir, &profile_name)).await?;
+
+  info!("[profiles] 已删除配置方案: {}", name);
+  Ok(())
 }
 
 /// 退出应用
@@ -602,12 +1324,16 @@ async fn clear_web_caches(app: tauri::AppHandle) -> Result<(), String> {
 /// 调试：获取配置文件路径
 #[tauri::command]
 async fn get_config_path_debug() -> Result<String, String> {
-  let path = get_config_path();
+  let app_paths = paths::app_paths()?;
+  let path = app_paths.config_file();
   let content = match fs::read_to_string(&path).await {
     Ok(c) => c,
     Err(e) => format!("读取失败: {}", e),
   };
-  Ok(format!("路径: {:?}\n内容: {}", path, content))
+  Ok(format!(
+    "路径: {:?}\n目录: 配置 {:?}，数据 {:?}，状态 {:?}（来源 {:?}）\n内容: {}",
+    path, app_paths.config_dir, app_paths.data_dir, app_paths.state_dir, app_paths.source, content
+  ))
 }
 
 /// 发送系统通知
@@ -716,40 +1442,119 @@ async fn send_wechat_work_notification(
   }
 }
 
+/// 按指定渠道发送通知
+/// 各渠道独立发送，单个渠道失败不影响其他渠道
+async fn dispatch_notification(
+  config: &AppConfig,
+  channels: &[alerts::NotificationChannel],
+  title: &str,
+  body: &str,
+) {
+  for channel in channels {
+    match channel {
+      alerts::NotificationChannel::System => {
+        let _ = send_system_notification(title, body).await;
+      }
+      alerts::NotificationChannel::WechatWork => {
+        let _ = send_wechat_work_notification(
+          &config.wechat_work_webhook_url,
+          title,
+          body,
+        ).await;
+      }
+    }
+  }
+}
+
+/// 评估告警状态并发送预警通知
+/// 同一级别只在越过阈值时通知一次，回落到回差以下或冷却时间到期后才会再次通知
+/// 返回本次触发的阈值级别，没有需要通知的级别时返回 None
+async fn evaluate_usage_alerts(app_state: &Arc<AppState>, usage: f64) -> Option<alerts::ThresholdTier> {
+  let config = {
+    let state = app_state.config.lock().await;
+    state.clone()
+  };
+
+  let (evaluation, snooze) = {
+    let mut alert_state = app_state.alerts.lock().await;
+    let evaluation = alert_state.evaluate(
+      &config.threshold_tiers,
+      usage,
+      app_state.clock.now_millis(),
+      config.alert_hysteresis,
+      std::time::Duration::from_secs(config.alert_cooldown_minutes as u64 * 60),
+    );
+    if evaluation.changed {
+      save_alert_state(&alert_state).await;
+    }
+    (evaluation, alert_state.snooze.clone())
+  };
+  let now = app_state.clock.now_millis();
+
+  // 回落到阈值以下的恢复通知（只通知最高的一个级别）
+  if config.notify_recovery {
+    if let Some(rearmed) = evaluation
+      .rearmed
+      .iter()
+      .max_by(|a, b| a.threshold.total_cmp(&b.threshold))
+      .filter(|tier| !snooze.is_snoozed(tier.severity, now))
+    {
+      info!("使用量 {:.1}% 已回落到 {:?} 阈值 {:.1}% 以下", usage, rearmed.severity, rearmed.threshold);
+      let title = "MiniMax 使用量已回落";
+      let body = format!(
+        "当前使用量 {:.1}%，已回落到{}阈值 {:.1}% 以下。",
+        usage,
+        rearmed.severity.label(),
+        rearmed.threshold
+      );
+      dispatch_notification(&config, &rearmed.channels, title, &body).await;
+    }
+  }
+
+  // 静音中的级别保持布防，静音结束后的读数仍高于阈值时再通知
+  for tier in &evaluation.suppressed {
+    info!(
+      "使用量 {:.1}% 越过 {:?} 阈值 {:.1}%，但该级别处于静音中，暂不通知",
+      usage, tier.severity, tier.threshold
+    );
+  }
+
+  let tier = evaluation.fired?;
+
+  info!(
+    "发送预警通知，使用量: {:.1}%, 触发级别: {:?} ({:.1}%)",
+    usage, tier.severity, tier.threshold
+  );
+
+  let title = format!("MiniMax 使用量{}", tier.severity.label());
+  let body = tier.render_message(usage);
+  dispatch_notification(&config, &tier.channels, &title, &body).await;
+
+  Some(tier)
+}
+
 /// 发送预警通知
-/// 当使用量超过阈值时发送预警通知（系统通知 + 企业微信）
+/// 由后端告警状态去重，返回本次触发的阈值级别；已通知过或未达到阈值时返回 None
 #[tauri::command]
 async fn send_warning_notification(
+  app_state: tauri::State<'_, Arc<AppState>>,
   usage: f64,
-  threshold: f64,
-) -> Result<(), String> {
-  info!("发送预警通知，使用量: {}%, 阈值: {}%", usage, threshold);
+) -> Result<Option<alerts::ThresholdTier>, String> {
+  let tier = evaluate_usage_alerts(app_state.inner(), usage).await;
+  if tier.is_none() {
+    info!("使用量 {:.1}% 没有需要通知的阈值级别", usage);
+  }
+  Ok(tier)
+}
 
-  let title = "MiniMax 使用量预警";
-  let body = format!("当前使用量已达到 {:.1}%，请注意配额使用情况！", usage);
+/// 测试系统通知
+/// 发送一个测试通知来验证通知功能是否正常
+#[tauri::command]
+async fn test_notification() -> Result<(), String> {
+  info!("发送测试通知");
 
   // 发送系统通知
-  let _ = send_system_notification(title, &body).await;
-
-  // 获取配置，发送企业微信通知
-  let config = get_settings().await?;
-  let _ = send_wechat_work_notification(
-    &config.wechat_work_webhook_url,
-    title,
-    &body,
-  ).await;
-
-  Ok(())
-}
-
-/// 测试系统通知
-/// 发送一个测试通知来验证通知功能是否正常
-#[tauri::command]
-async fn test_notification() -> Result<(), String> {
-  info!("发送测试通知");
-
-  // 发送系统通知
-  let _ = send_system_notification("MiniMax Monitor", "测试通知发送成功！").await;
+  let _ = send_system_notification("MiniMax Monitor", "测试通知发送成功！").await;
 
   Ok(())
 }
@@ -825,6 +1630,66 @@ async fn send_error_notification(
   Ok(())
 }
 
+/// 发送恢复通知
+/// 连续失败并已发送错误通知后，重新获取到使用量时调用
+async fn send_recovery_notification(usage: f64, config: &AppConfig) {
+  info!("发送恢复通知，使用量: {:.1}%", usage);
+
+  let title = "MiniMax 监控已恢复";
+  let body = format!("已重新获取到使用量数据，当前使用量: {:.1}%", usage);
+
+  // 发送系统通知
+  let _ = send_system_notification(title, &body).await;
+
+  // 发送企业微信通知
+  let _ = send_wechat_work_notification(
+    &config.wechat_work_webhook_url,
+    title,
+    &body,
+  ).await;
+}
+
+/// 发送数据过期通知
+/// 长时间没有获取到新数据时调用，提示监控可能已经失效
+async fn send_stale_notification(age_minutes: u64, config: &AppConfig) {
+  warn!("发送数据过期通知，已 {} 分钟没有新数据", age_minutes);
+
+  let title = "MiniMax 监控已失效";
+  let body = format!(
+    "已有 {} 分钟没有获取到使用量数据，页面可能已改版或登录已失效，请打开 MiniMax 页面检查。",
+    age_minutes
+  );
+
+  // 发送系统通知
+  let _ = send_system_notification(title, &body).await;
+
+  // 发送企业微信通知
+  let _ = send_wechat_work_notification(
+    &config.wechat_work_webhook_url,
+    title,
+    &body,
+  ).await;
+}
+
+/// 发送登录失效通知
+/// 隐藏的 MiniMax 窗口检测到登录页时调用
+async fn send_login_required_notification(config: &AppConfig) {
+  warn!("发送登录失效通知");
+
+  let title = "MiniMax 登录已失效";
+  let body = "MiniMax 登录已过期，无法获取使用量，请打开 MiniMax 页面重新登录。";
+
+  // 发送系统通知
+  let _ = send_system_notification(title, body).await;
+
+  // 发送企业微信通知
+  let _ = send_wechat_work_notification(
+    &config.wechat_work_webhook_url,
+    title,
+    body,
+  ).await;
+}
+
 /// 打开 URL
 /// 使用系统默认浏览器打开指定 URL
 #[tauri::command]
@@ -880,25 +1745,141 @@ async fn open_minmax_window(app: tauri::AppHandle) -> Result<(), String> {
     return Ok(());
   }
 
+  info!("创建 MiniMax 窗口: {}", MINMAX_USAGE_URL);
+
+  // 用户手动打开窗口，需要显示并显示在任务栏
+  create_minmax_window(&app, true).await.map(|_| ())
+}
+
+/// 开始跟踪一次页面加载，返回本次加载序号
+fn begin_page_load(app_state: &Arc<AppState>) -> u64 {
+  app_state.page_load().begin()
+}
+
+/// 处理 MiniMax 窗口的页面加载事件
+/// 由 WebviewWindowBuilder::on_page_load 回调调用；同步更新状态，保证 Started / Finished 按顺序处理
+fn handle_page_load(app: &tauri::AppHandle, event: tauri::webview::PageLoadEvent, url: &str) {
+  let app_state = app.state::<Arc<AppState>>();
+  match event {
+    tauri::webview::PageLoadEvent::Started => {
+      info!("[page_load] 开始加载: {}", url);
+      app_state.page_load().on_started();
+    }
+    tauri::webview::PageLoadEvent::Finished => {
+      info!("[page_load] 加载完成: {}", url);
+      if !app_state.page_load().on_finished() {
+        info!("[page_load] 不是最近一次刷新的加载，继续等待");
+      }
+    }
+  }
+}
+
+/// 创建 MiniMax 窗口
+/// 统一设置注入脚本与页面加载回调，并开始跟踪首次加载，返回首次加载的序号
+async fn create_minmax_window(app: &tauri::AppHandle, visible: bool) -> Result<u64, String> {
   let url = MINMAX_USAGE_URL
     .parse()
     .map_err(|e| format!("MiniMax URL 解析失败: {}", e))?;
 
-  info!("创建 MiniMax 窗口: {}", MINMAX_USAGE_URL);
+  let app_state = app.state::<Arc<AppState>>().inner().clone();
+  let sequence = begin_page_load(&app_state);
 
-  tauri::WebviewWindowBuilder::new(&app, MINMAX_WINDOW_LABEL, tauri::WebviewUrl::External(url))
+  // 注入首次加载的序号，之后的刷新由 do_trigger_fetch_usage 写入 sessionStorage
+  let init_script = format!("window.__MINMAX_INITIAL_SEQ__ = {};\n{}", sequence, MINMAX_INIT_SCRIPT);
+
+  let page_load_app = app.clone();
+  tauri::WebviewWindowBuilder::new(app, MINMAX_WINDOW_LABEL, tauri::WebviewUrl::External(url))
     .title("MiniMax")
     .inner_size(1100.0, 800.0)
     .resizable(true)
     .center()
-    .initialization_script(MINMAX_INIT_SCRIPT)
-    // 用户手动打开窗口，需要显示并显示在任务栏
-    .visible(true)
-    .skip_taskbar(false)
+    .initialization_script(&init_script)
+    .visible(visible)
+    // 后台静默窗口不显示在任务栏
+    .skip_taskbar(!visible)
+    .on_page_load(move |_webview, payload| {
+      handle_page_load(&page_load_app, payload.event(), payload.url().as_str());
+    })
     .build()
     .map_err(|e| format!("创建 MiniMax 窗口失败: {}", e))?;
 
-  Ok(())
+  app_state.page_load().window_created_at = Some(app_state.clock.now_millis());
+
+  watch_page_load(app.clone(), sequence);
+  Ok(sequence)
+}
+
+/// 重建 MiniMax 窗口
+/// 销毁现有窗口后用相同的注入脚本重新创建，保留原有的可见状态，返回新窗口首次加载的序号
+async fn recreate_minmax_window(app: &tauri::AppHandle) -> Result<u64, String> {
+  let visible = match app.get_webview_window(MINMAX_WINDOW_LABEL) {
+    Some(win) => {
+      let visible = win.is_visible().unwrap_or(false);
+      win.destroy().map_err(|e| format!("销毁 MiniMax 窗口失败: {}", e))?;
+      visible
+    }
+    None => false,
+  };
+
+  // 等待窗口真正移除后再用相同 label 创建
+  for _ in 0..50 {
+    if app.get_webview_window(MINMAX_WINDOW_LABEL).is_none() {
+      break;
+    }
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+  }
+
+  info!("[self_heal] 重建 MiniMax 窗口（可见: {}）", visible);
+  create_minmax_window(app, visible).await
+}
+
+/// 监视一次页面加载
+/// 超时仍未收到 Finished 事件时销毁并重建 MiniMax 窗口；
+/// 窗口可见（用户可能正在登录）或仍在重建退避期内时不重建，只上报本次加载失败
+fn watch_page_load(app: tauri::AppHandle, sequence: u64) {
+  tauri::async_runtime::spawn(async move {
+    let app_state = app.state::<Arc<AppState>>().inner().clone();
+    let timeout_secs = {
+      let state = app_state.config.lock().await;
+      state.page_load_timeout_secs.max(1) as u64
+    };
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(timeout_secs)).await;
+
+    // 期间已加载完成或开始了新的加载，无需处理
+    if !app_state.page_load().is_pending(sequence) {
+      return;
+    }
+
+    let visible = app
+      .get_webview_window(MINMAX_WINDOW_LABEL)
+      .is_some_and(|win| win.is_visible().unwrap_or(false));
+    let decision = if visible {
+      Err("MiniMax 窗口正在显示，不自动重建".to_string())
+    } else {
+      app_state
+        .page_load()
+        .try_recreate(app_state.clock.now_millis())
+        .map_err(|wait| format!("距下次允许重建还需 {} 秒", wait.as_secs()))
+    };
+
+    if let Err(reason) = decision {
+      warn!("[self_heal] 页面加载 #{} 超过 {} 秒未完成，{}", sequence, timeout_secs, reason);
+      let _ = app.emit(
+        "minmax-usage",
+        serde_json::json!({ "error": format!("页面加载超过 {} 秒未完成", timeout_secs), "seq": sequence }),
+      );
+      return;
+    }
+
+    warn!("[self_heal] 页面加载 #{} 超过 {} 秒未完成，重建 MiniMax 窗口", sequence, timeout_secs);
+    if let Err(e) = recreate_minmax_window(&app).await {
+      error!("[self_heal] 重建 MiniMax 窗口失败: {}", e);
+      // 重建过程中可能已经分配了新的加载序号，错误归属最近一次加载
+      let latest = app_state.page_load().latest();
+      let _ = app.emit("minmax-usage", serde_json::json!({ "error": e, "seq": latest }));
+    }
+  });
 }
 
 /// 读取剪贴板文本
@@ -987,7 +1968,9 @@ async fn get_usage() -> Result<f64, String> {
 /// 内部函数：触发前端获取使用量（供定时任务直接调用）
 /// 这个函数是普通函数，不是 tauri command
 /// 静默模式：只在后台静默获取数据，不显示窗口
-fn do_trigger_fetch_usage(app: &tauri::AppHandle) {
+/// 成功时返回本次页面加载的序号，注入脚本的上报会带上该序号；
+/// 窗口不存在或刷新失败时返回错误信息
+async fn do_trigger_fetch_usage(app: &tauri::AppHandle) -> Result<u64, String> {
   info!("[do_trigger_fetch_usage] 开始执行（静默模式）");
 
   // 使用 app.emit 广播事件到主窗口（更新提示信息）
@@ -997,31 +1980,169 @@ fn do_trigger_fetch_usage(app: &tauri::AppHandle) {
   // 获取 MiniMax 窗口
   let window = app.get_webview_window(MINMAX_WINDOW_LABEL);
   if let Some(win) = window {
+    let app_state = app.state::<Arc<AppState>>().inner().clone();
+
+    // 隐藏窗口运行时间过长时直接重建，限制长时间运行的内存增长
+    // 新窗口加载后 initialization_script 同样会提取数据
+    if should_recycle_window(&app_state, &win).await {
+      info!("[do_trigger_fetch_usage] MiniMax 窗口已运行较长时间，重建窗口以释放内存");
+      return recreate_minmax_window(app).await.map_err(|e| {
+        let _ = app.emit("minmax-usage", serde_json::json!({ "error": e }));
+        e
+      });
+    }
+
     info!("[do_trigger_fetch_usage] 找到 MiniMax 窗口，正在刷新页面以获取最新数据...");
 
     // 刷新页面
     // 页面刷新后，initialization_script 会自动执行并提取数据
-    match win.eval("window.location.reload()") {
-      Ok(_) => info!("[do_trigger_fetch_usage] 页面刷新命令已发送"),
-      Err(e) => {
-        error!("[do_trigger_fetch_usage] 页面刷新失败: {}", e);
-        let _ = app.emit("minmax-usage", serde_json::json!({ "error": "页面刷新失败" }));
-      }
+    // 刷新前把序号写入 sessionStorage，新页面的注入脚本读取后随上报一起发送
+    let sequence = begin_page_load(&app_state);
+    let reload_script = format!(
+      "try {{ sessionStorage.setItem('minmaxLoadSeq', '{}'); }} catch (e) {{}}\nwindow.location.reload();",
+      sequence
+    );
+    if let Err(e) = win.eval(&reload_script) {
+      error!("[do_trigger_fetch_usage] 页面刷新失败: {}", e);
+      let _ = app.emit("minmax-usage", serde_json::json!({ "error": "页面刷新失败", "seq": sequence }));
+      return Err(format!("页面刷新失败: {}", e));
     }
+    info!("[do_trigger_fetch_usage] 页面刷新命令已发送");
+
+    // 超时未加载完成时自动重建窗口
+    watch_page_load(app.clone(), sequence);
+
+    info!("[do_trigger_fetch_usage] 执行完成，页面加载 #{}", sequence);
+    Ok(sequence)
   } else {
     warn!("[do_trigger_fetch_usage] MiniMax 窗口不存在");
     let _ = app.emit("minmax-usage", serde_json::json!({ "error": "MiniMax 窗口不存在" }));
+    Err("MiniMax 窗口不存在".to_string())
+  }
+}
+
+/// 判断隐藏的 MiniMax 窗口是否需要定期重建
+/// 用户正在查看（窗口可见）时不重建，避免打断登录等操作
+async fn should_recycle_window(app_state: &Arc<AppState>, win: &tauri::WebviewWindow) -> bool {
+  let recycle_hours = {
+    let state = app_state.config.lock().await;
+    state.webview_recycle_hours
+  };
+  if recycle_hours == 0 || win.is_visible().unwrap_or(false) {
+    return false;
   }
 
-  info!("[do_trigger_fetch_usage] 执行完成");
+  let created_at = app_state.page_load().window_created_at;
+  match created_at {
+    Some(created_at) => app_state.clock.now_millis().saturating_sub(created_at) >= recycle_hours as u64 * 3_600_000,
+    None => false,
+  }
 }
 
 /// 触发前端获取使用量
 /// Rust 后端定时任务调用此命令，通知前端打开 MiniMax 页面并获取使用量
 #[tauri::command]
 async fn trigger_fetch_usage(app: tauri::AppHandle) -> Result<(), String> {
-  do_trigger_fetch_usage(&app);
-  Ok(())
+  do_trigger_fetch_usage(&app).await.map(|_| ())
+}
+
+/// check_now 默认等待时间（秒）
+const DEFAULT_CHECK_NOW_TIMEOUT_SECS: u64 = 30;
+
+/// 一次成功的使用量读数
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct UsageReading {
+  /// 使用量（百分比）
+  #[serde(rename = "percent")]
+  pub percent: f64,
+  /// 读取时间（Unix 毫秒时间戳）
+  #[serde(rename = "timestamp")]
+  pub timestamp: u64,
+}
+
+/// check_now 的错误类型
+/// 序列化为 { kind, ... }，前端可按 kind 区分处理
+#[derive(serde::Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum CheckNowError {
+  /// 触发刷新失败（如窗口不存在）
+  Trigger { message: String },
+  /// 超时未收到结果
+  Timeout {
+    #[serde(rename = "timeoutSecs")]
+    timeout_secs: u64,
+  },
+  /// 页面已加载但未能提取到使用量
+  Extraction { message: String },
+  /// 登录已失效，需要在 MiniMax 窗口中重新登录
+  LoginRequired { url: Option<String> },
+}
+
+/// 当前 Unix 毫秒时间戳
+fn now_millis() -> u64 {
+  std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or(0)
+}
+
+/// 立即检查使用量
+/// 触发隐藏窗口刷新，并等待本次刷新后的页面返回最新读数
+/// 刷新前旧页面仍可能上报（定时的 fetchUsageFromApi、DOM 变化触发的 tryCollect），
+/// 上一次加载的超时错误也可能在本次刷新后才到达，按加载序号忽略这些上报
+#[tauri::command]
+async fn check_now(
+  app: tauri::AppHandle,
+  app_state: tauri::State<'_, Arc<AppState>>,
+  timeout_secs: Option<u64>,
+) -> Result<UsageReading, CheckNowError> {
+  let timeout_secs = timeout_secs.unwrap_or(DEFAULT_CHECK_NOW_TIMEOUT_SECS).max(1);
+  info!("[check_now] 立即检查，超时 {} 秒", timeout_secs);
+
+  // 先订阅再触发，避免错过刷新后很快到达的事件
+  let mut receiver = app_state.page_events.subscribe();
+
+  let sequence = do_trigger_fetch_usage(&app).await.map_err(|message| CheckNowError::Trigger { message })?;
+  let is_stale = |seq: Option<u64>| !page_load::belongs_to_check(seq, sequence);
+
+  let wait = async {
+    loop {
+      match receiver.recv().await {
+        Ok(PageEvent::Usage(payload)) if is_stale(payload.seq) => {
+          info!("[check_now] 忽略不属于本次检查的上报（加载 #{:?}），等待加载 #{}", payload.seq, sequence);
+          continue;
+        }
+        Ok(PageEvent::Usage(UsageEventPayload { percent: Some(p), .. })) if p.is_finite() && (0.0..=100.0).contains(&p) => {
+          return Ok(UsageReading { percent: p, timestamp: app_state.clock.now_millis() });
+        }
+        Ok(PageEvent::Usage(UsageEventPayload { error: Some(message), .. })) => {
+          return Err(CheckNowError::Extraction { message });
+        }
+        Ok(PageEvent::LoginRequired(payload)) if !is_stale(payload.seq) => {
+          return Err(CheckNowError::LoginRequired { url: payload.url });
+        }
+        Ok(_) => continue,
+        Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+          warn!("[check_now] 事件接收滞后，丢失 {} 条", n);
+          continue;
+        }
+        Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+          return Err(CheckNowError::Extraction { message: "页面事件通道已关闭".to_string() });
+        }
+      }
+    }
+  };
+
+  match tokio::time::timeout(tokio::time::Duration::from_secs(timeout_secs), wait).await {
+    Ok(result) => {
+      info!("[check_now] 检查结束: {:?}", result);
+      result
+    }
+    Err(_) => {
+      warn!("[check_now] 等待 {} 秒仍未收到使用量", timeout_secs);
+      Err(CheckNowError::Timeout { timeout_secs })
+    }
+  }
 }
 
 /// 定时检查使用量
@@ -1035,28 +2156,543 @@ async fn scheduled_check(app: &tauri::AppHandle, app_state: &Arc<AppState>) {
     state.clone()
   };
 
-  info!("[scheduled_check] 配置: 阈值={:.1}%, 间隔={}分钟", config.warning_threshold, config.check_interval);
+  info!("[scheduled_check] 配置: 阈值级别={}个, 间隔={}分钟", config.threshold_tiers.len(), config.check_interval);
 
   // 调用内部函数触发前端获取使用量
-  do_trigger_fetch_usage(app);
+  // 触发失败时 do_trigger_fetch_usage 已广播 minmax-usage 错误事件，由 handle_usage_event 统一计入失败次数，
+  // 这里只记录日志，避免重复计数
+  if let Err(e) = do_trigger_fetch_usage(app).await {
+    warn!("[scheduled_check] 触发检查失败: {}", e);
+  }
 
   info!("[scheduled_check] 检查完成");
 }
 
+/// 记录一次失败的检查
+/// 触发失败与页面提取失败都经由 minmax-usage 错误事件到达这里，共用同一个失败统计，
+/// 连续失败达到阈值后才升级为错误通知，避免偶发失败打扰用户。
+/// 一次检查可能先后上报提取错误与加载超时，按加载序号每次检查只计一次；
+/// 登录失效期间的失败已经由登录失效通知告知用户，不计入
+async fn record_check_failure(app_state: &Arc<AppState>, seq: Option<u64>, error: &str) {
+  if !app_state.login.lock().await.counts_failures() {
+    info!("[check_failure] 登录已失效，不计入失败: {}", error);
+    return;
+  }
+
+  let config = {
+    let state = app_state.config.lock().await;
+    state.clone()
+  };
+
+  let latest = app_state.page_load().latest();
+  let (should_notify, failures) = {
+    let mut tracker = app_state.failures.lock().await;
+    if !tracker.claim(seq, latest) {
+      info!("[check_failure] 加载 #{:?} 的失败已计入或已过期，忽略: {}", seq, error);
+      return;
+    }
+    let should_notify = tracker.record_failure(error, config.failure_notify_after);
+    (should_notify, tracker.consecutive_failures)
+  };
+
+  warn!("[check_failure] 检查失败（连续 {} 次）: {}", failures, error);
+
+  if should_notify {
+    let message = format!("连续 {} 次检查失败，最近一次原因: {}", failures, error);
+    let _ = send_error_notification(message, config).await;
+  }
+}
+
+/// 处理 minmax-usage 事件
+/// 收到有效使用量时清空失败计数，必要时发送恢复通知；收到错误时计入失败次数
+async fn handle_usage_event(app_state: &Arc<AppState>, payload: UsageEventPayload) {
+  let percent = match payload.percent {
+    Some(p) if p.is_finite() && (0.0..=100.0).contains(&p) => p,
+    _ => {
+      if let Some(e) = payload.error {
+        info!("[usage_event] 收到错误事件: {}", e);
+        record_check_failure(app_state, payload.seq, &e).await;
+      }
+      return;
+    }
+  };
+
+  let now = app_state.clock.now_millis();
+  let previous = {
+    let mut last = app_state.last_reading.lock().await;
+    last.replace(UsageReading { percent, timestamp: now })
+  };
+
+  // 与上一次读数比较，检测配额是否已重置
+  let mut quota_reset = false;
+  if let Some(previous) = previous {
+    let reset_at = *app_state.reset_at.lock().await;
+    if quota::is_quota_reset(previous.percent, previous.timestamp, percent, now, reset_at) {
+      quota_reset = true;
+      handle_quota_reset(app_state, previous.percent, percent).await;
+    }
+  }
+
+  // 记录历史读数，新的配额周期从头开始
+  {
+    let mut history = app_state.history.lock().await;
+    if quota_reset {
+      history.clear();
+    }
+    history.push_back(UsageReading { percent, timestamp: now });
+    while history.len() > USAGE_HISTORY_CAPACITY {
+      history.pop_front();
+    }
+  }
+
+  // 后端评估阈值告警（去重后发送）
+  evaluate_usage_alerts(app_state, percent).await;
+
+  // 趋势预测告警
+  evaluate_forecast_alert(app_state).await;
+
+  // 异常消耗告警
+  evaluate_jump_alert(app_state, percent, now).await;
+
+  // 自定义规则告警
+  evaluate_rule_alerts(app_state, percent, previous.as_ref(), now).await;
+
+  let recovered_from_failures = {
+    let mut tracker = app_state.failures.lock().await;
+    tracker.record_success()
+  };
+  let recovered_from_stale = {
+    let mut watchdog = app_state.watchdog.lock().await;
+    watchdog.reset()
+  };
+  let recovered = recovered_from_failures || recovered_from_stale;
+
+  // 能拿到数据说明已重新登录
+  app_state.login.lock().await.mark_recovered();
+
+  if recovered {
+    let config = {
+      let state = app_state.config.lock().await;
+      state.clone()
+    };
+    send_recovery_notification(percent, &config).await;
+  }
+}
+
+/// 评估异常消耗告警
+/// 当前读数相对回看窗口内较早读数的消耗速度超过配置的上限时通知一次，速度恢复正常后重新布防
+async fn evaluate_jump_alert(app_state: &Arc<AppState>, usage: f64, now: u64) {
+  let config = {
+    let state = app_state.config.lock().await;
+    state.clone()
+  };
+
+  let samples: Vec<(u64, f64)> = {
+    let history = app_state.history.lock().await;
+    history.iter().map(|r| (r.timestamp, r.percent)).collect()
+  };
+  let jump = forecast::detect_jump(
+    &samples,
+    config.anomaly_window_minutes.max(1) as u64 * 60_000,
+    config.anomaly_rate_per_minute,
+  );
+
+  let should_alert = {
+    let mut alerted = app_state.anomaly_alerted.lock().await;
+    match &jump {
+      Some(_) => !std::mem::replace(&mut *alerted, true),
+      None => {
+        *alerted = false;
+        false
+      }
+    }
+  };
+  let Some(jump) = jump.filter(|_| should_alert) else {
+    return;
+  };
+
+  let body = format!(
+    "使用量在 {:.1} 分钟内从 {:.1}% 上升到 {:.1}%（+{:.1} 个百分点，约每分钟 {:.1}%），超过上限每分钟 {:.1}%。",
+    jump.span_millis as f64 / 60_000.0,
+    usage - jump.delta,
+    usage,
+    jump.delta,
+    jump.rate_per_minute,
+    config.anomaly_rate_per_minute
+  );
+  warn!("[anomaly] {}", body);
+
+  let severity = config.anomaly_severity;
+  if app_state.alerts.lock().await.snooze.is_snoozed(severity, now) {
+    info!("[anomaly] {:?} 级别处于静音中，不发送通知", severity);
+    return;
+  }
+  // 按告警级别对应阈值配置的渠道发送
+  let title = format!("MiniMax 异常消耗{}", severity.label());
+  let channels = alerts::severity_channels(&config.threshold_tiers, severity);
+  dispatch_notification(&config, &channels, &title, &body).await;
+}
+
+/// 评估自定义告警规则
+/// 规则条件由假变真时按规则的级别与渠道发送通知，处于静音中的级别不发送
+async fn evaluate_rule_alerts(app_state: &Arc<AppState>, usage: f64, previous: Option<&UsageReading>, now: u64) {
+  if app_state.rules.lock().await.is_empty() {
+    return;
+  }
+  let config = {
+    let state = app_state.config.lock().await;
+    state.clone()
+  };
+
+  let reset_at = *app_state.reset_at.lock().await;
+  let history = {
+    let history = app_state.history.lock().await;
+    history.iter().map(|r| (r.timestamp, r.percent)).collect()
+  };
+  let local = chrono::Local::now();
+  let context = rules::RuleContext {
+    usage,
+    history,
+    previous: previous.map(|r| r.percent),
+    elapsed_minutes: previous.map(|r| now.saturating_sub(r.timestamp) as f64 / 60_000.0),
+    reset_in_minutes: reset_at.map(|reset_at| (reset_at as f64 - now as f64) / 60_000.0),
+    hour: local.hour() as f64,
+    minute: local.minute() as f64,
+  };
+
+  let fired = {
+    let mut rule_state = app_state.rules.lock().await;
+    rule_state.evaluate(&context)
+  };
+  if fired.is_empty() {
+    return;
+  }
+
+  let snooze = app_state.alerts.lock().await.snooze.clone();
+  for rule in fired {
+    if snooze.is_snoozed(rule.severity, now) {
+      info!("[rules] 规则「{}」已触发，但 {:?} 级别处于静音中，不发送通知", rule.name, rule.severity);
+      continue;
+    }
+    info!("[rules] 规则「{}」已触发: {}", rule.name, rule.condition);
+    let title = format!("MiniMax 使用量{}：{}", rule.severity.label(), rule.name);
+    dispatch_notification(&config, &rule.channels, &title, &rule.render_message(usage)).await;
+  }
+}
+
+/// 确认严重告警
+/// 停止当前严重告警的重复提醒；返回是否有需要确认的告警
+#[tauri::command]
+async fn acknowledge_alert(app_state: tauri::State<'_, Arc<AppState>>) -> Result<bool, String> {
+  let mut alert_state = app_state.alerts.lock().await;
+  let acknowledged = alert_state.acknowledge();
+  if acknowledged {
+    info!("[escalation] 用户已确认严重告警，停止重复提醒");
+    save_alert_state(&alert_state).await;
+  }
+  Ok(acknowledged)
+}
+
+/// 静音告警
+/// 在 `duration_minutes` 分钟内不发送告警通知；`tier` 为空时静音全部级别。返回生效中的静音
+#[tauri::command]
+async fn snooze_alerts(
+  app_state: tauri::State<'_, Arc<AppState>>,
+  duration_minutes: u32,
+  tier: Option<alerts::Severity>,
+) -> Result<Vec<alerts::ActiveSnooze>, String> {
+  if duration_minutes == 0 {
+    return Err("静音时长必须大于 0 分钟".to_string());
+  }
+
+  let now = app_state.clock.now_millis();
+  let until = now.saturating_add(duration_minutes as u64 * 60_000);
+  let mut alert_state = app_state.alerts.lock().await;
+  alert_state.snooze.prune(now);
+  alert_state.snooze.snooze(tier, until);
+  save_alert_state(&alert_state).await;
+
+  info!("[snooze] 静音 {:?} {} 分钟", tier, duration_minutes);
+  Ok(alert_state.snooze.active(now))
+}
+
+/// 取消告警静音
+/// `tier` 为空时取消所有静音。返回仍生效的静音
+#[tauri::command]
+async fn unsnooze_alerts(
+  app_state: tauri::State<'_, Arc<AppState>>,
+  tier: Option<alerts::Severity>,
+) -> Result<Vec<alerts::ActiveSnooze>, String> {
+  let now = app_state.clock.now_millis();
+  let mut alert_state = app_state.alerts.lock().await;
+  alert_state.snooze.unsnooze(tier);
+  alert_state.snooze.prune(now);
+  save_alert_state(&alert_state).await;
+
+  info!("[snooze] 取消静音 {:?}", tier);
+  Ok(alert_state.snooze.active(now))
+}
+
+/// 获取生效中的告警静音
+#[tauri::command]
+async fn get_snooze_status(app_state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<alerts::ActiveSnooze>, String> {
+  let now = app_state.clock.now_millis();
+  let mut alert_state = app_state.alerts.lock().await;
+  if alert_state.snooze.prune(now) {
+    save_alert_state(&alert_state).await;
+  }
+  Ok(alert_state.snooze.active(now))
+}
+
+/// 历史读数保留上限
+const USAGE_HISTORY_CAPACITY: usize = 500;
+
+/// 根据历史读数计算使用量预测
+async fn compute_forecast(app_state: &Arc<AppState>, config: &AppConfig) -> Option<forecast::Forecast> {
+  let samples: Vec<(u64, f64)> = {
+    let history = app_state.history.lock().await;
+    history.iter().map(|r| (r.timestamp, r.percent)).collect()
+  };
+  let reset_at = *app_state.reset_at.lock().await;
+
+  forecast::forecast(
+    &samples,
+    config.forecast_window_minutes.max(1) as u64 * 60_000,
+    reset_at,
+  )
+}
+
+/// 评估预测告警
+/// 预计在重置前用满 100% 时通知一次，预测不再早于重置时重新布防
+async fn evaluate_forecast_alert(app_state: &Arc<AppState>) {
+  let config = {
+    let state = app_state.config.lock().await;
+    state.clone()
+  };
+  if !config.forecast_alert {
+    return;
+  }
+
+  let Some(prediction) = compute_forecast(app_state, &config).await else {
+    return;
+  };
+
+  let snoozed = app_state.alerts.lock().await.snooze.is_all_snoozed(app_state.clock.now_millis());
+  let should_alert = {
+    let mut alerted = app_state.forecast_alerted.lock().await;
+    if !prediction.exhausts_before_reset {
+      *alerted = false;
+      return;
+    }
+    // 静音期间不标记为已通知，静音结束后预测仍成立时再通知
+    if snoozed {
+      return;
+    }
+    !std::mem::replace(&mut *alerted, true)
+  };
+  if !should_alert {
+    return;
+  }
+
+  let (Some(exhaustion_at), Some(reset_at)) = (prediction.projected_exhaustion_at, prediction.reset_at) else {
+    return;
+  };
+  let now = app_state.clock.now_millis();
+  let title = "MiniMax 使用量预测";
+  let body = format!(
+    "按当前速度（每小时约 {:.1}%），预计 {} 后用满 100%，而距离配额重置还有 {}。",
+    prediction.rate_per_hour.unwrap_or_default(),
+    forecast::format_duration_millis(exhaustion_at.saturating_sub(now)),
+    forecast::format_duration_millis(reset_at.saturating_sub(now)),
+  );
+  warn!("[forecast] {}", body);
+  dispatch_notification(&config, &alerts::all_channels(), title, &body).await;
+}
+
+/// 获取使用量预测
+/// 返回消耗速度、预计用满时间以及是否会在重置前用满；没有任何读数时返回 None
+#[tauri::command]
+async fn get_forecast(app_state: tauri::State<'_, Arc<AppState>>) -> Result<Option<forecast::Forecast>, String> {
+  let config = {
+    let state = app_state.config.lock().await;
+    state.clone()
+  };
+  Ok(compute_forecast(app_state.inner(), &config).await)
+}
+
+/// 处理配额重置
+/// 重新布防所有告警级别，并按配置发送"配额已重置"通知；全部静音期间不发送
+async fn handle_quota_reset(app_state: &Arc<AppState>, previous: f64, current: f64) {
+  info!("[quota_reset] 检测到配额重置: {:.1}% -> {:.1}%", previous, current);
+
+  {
+    let mut alert_state = app_state.alerts.lock().await;
+    alert_state.rearm_all();
+    save_alert_state(&alert_state).await;
+  }
+
+  let config = {
+    let state = app_state.config.lock().await;
+    state.clone()
+  };
+  if !config.notify_quota_reset {
+    return;
+  }
+  if app_state.alerts.lock().await.snooze.is_all_snoozed(app_state.clock.now_millis()) {
+    info!("[quota_reset] 全部告警处于静音中，不发送通知");
+    return;
+  }
+
+  // 配额重置属于提醒，按“提醒”级别阈值配置的渠道发送
+  let title = "MiniMax 配额已重置";
+  let body = format!("使用量已从 {:.1}% 回到 {:.1}%，新的配额周期已开始。", previous, current);
+  let channels = alerts::severity_channels(&config.threshold_tiers, alerts::Severity::Info);
+  dispatch_notification(&config, &channels, title, &body).await;
+}
+
+/// 处理 minmax-reset-time 事件
+/// 将剩余时间文本换算为绝对重置时间保存
+async fn handle_reset_time_event(app_state: &Arc<AppState>, reset_time: &str) {
+  match quota::parse_reset_duration(reset_time) {
+    Some(duration) => {
+      let reset_at = app_state.clock.now_millis().saturating_add(duration.as_millis() as u64);
+      info!("[reset_time] 剩余 {:?}，预计重置时间戳 {}", duration, reset_at);
+      let mut state = app_state.reset_at.lock().await;
+      *state = Some(reset_at);
+    }
+    None => warn!("[reset_time] 无法解析剩余时间: {}", reset_time),
+  }
+}
+
+/// minmax-reset-time 事件载荷
+#[derive(serde::Deserialize, Debug)]
+struct ResetTimeEventPayload {
+  #[serde(rename = "resetTime")]
+  reset_time: String,
+}
+
+/// 处理 minmax-login-required 事件
+/// 每次登录失效只通知一次，重新获取到数据后才会再次通知
+async fn handle_login_required(app: &tauri::AppHandle, app_state: &Arc<AppState>) {
+  if !app_state.login.lock().await.mark_required() {
+    info!("[login_required] 已通知过登录失效，忽略重复事件");
+    return;
+  }
+
+  let config = {
+    let state = app_state.config.lock().await;
+    state.clone()
+  };
+
+  send_login_required_notification(&config).await;
+
+  if config.open_window_on_login_required {
+    info!("[login_required] 自动显示 MiniMax 窗口以便重新登录");
+    if let Err(e) = open_minmax_window(app.clone()).await {
+      error!("[login_required] 显示 MiniMax 窗口失败: {}", e);
+    }
+  }
+}
+
+/// minmax-usage 事件载荷
+/// 注入脚本上报 percent，后端触发失败时上报 error
+#[derive(serde::Deserialize, Clone, Debug)]
+struct UsageEventPayload {
+  percent: Option<f64>,
+  error: Option<String>,
+  /// 上报所属的页面加载序号，没有对应页面加载的触发失败（如窗口不存在）没有序号
+  seq: Option<u64>,
+}
+
+/// minmax-login-required 事件载荷
+#[derive(serde::Deserialize, Clone, Debug)]
+struct LoginRequiredEventPayload {
+  url: Option<String>,
+  seq: Option<u64>,
+}
+
+/// 转发给 check_now 的页面事件
+#[derive(Clone, Debug)]
+enum PageEvent {
+  /// minmax-usage 事件
+  Usage(UsageEventPayload),
+  /// minmax-login-required 事件
+  LoginRequired(LoginRequiredEventPayload),
+}
+
 /// 应用状态
 struct AppState {
   /// 当前配置
   config: Mutex<AppConfig>,
-  /// 定时器是否运行中
-  timer_running: Mutex<bool>,
+  /// 定时检查的连续失败统计
+  failures: Mutex<scheduler::FailureTracker>,
+  /// 页面事件（使用量、登录失效）的后端广播通道，供 check_now 等待结果
+  page_events: tokio::sync::broadcast::Sender<PageEvent>,
+  /// 最近一次成功的使用量读数
+  last_reading: Mutex<Option<UsageReading>>,
+  /// 数据过期看门狗状态
+  watchdog: Mutex<scheduler::WatchdogState>,
+  /// 登录失效状态
+  login: Mutex<scheduler::LoginState>,
+  /// MiniMax 窗口的页面加载状态
+  page_load: std::sync::Mutex<page_load::PageLoadState>,
+  /// 配置文件的变更检测，记录本应用最近一次写入的内容
+  config_file: std::sync::Mutex<storage::ChangeDetector>,
+  /// 定时检查、后台任务与事件处理共用的时钟（可替换为测试时钟）
+  clock: Arc<dyn scheduler::Clock>,
+  /// 各阈值级别的告警状态（持久化到 alert_state.json）
+  alerts: Mutex<alerts::AlertState>,
+  /// 根据页面剩余时间推算的配额重置时间（Unix 毫秒）
+  reset_at: Mutex<Option<u64>>,
+  /// 本配额周期内的历史读数（按时间升序）
+  history: Mutex<std::collections::VecDeque<UsageReading>>,
+  /// 预测告警是否已发送
+  forecast_alerted: Mutex<bool>,
+  /// 重置前提醒状态
+  reminders: Mutex<quota::ReminderState>,
+  /// 自定义规则触发状态
+  rules: Mutex<rules::RuleState>,
+  /// 异常消耗告警是否已发送，消耗速度恢复正常后重新布防
+  anomaly_alerted: Mutex<bool>,
+  /// 配置变更后通知定时任务按新的间隔重新调度
+  /// 使用 notify_one：定时任务正在执行检查、没有等待通知时，通知会保留到下一次等待
+  reschedule: tokio::sync::Notify,
 }
 
 impl AppState {
+  /// 页面加载状态
+  /// 使用同步锁，页面加载回调可以直接更新；持有期间不能跨越 await
+  fn page_load(&self) -> std::sync::MutexGuard<'_, page_load::PageLoadState> {
+    self.page_load.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  /// 配置文件的变更检测
+  /// 使用同步锁，持有期间不能跨越 await
+  fn config_file(&self) -> std::sync::MutexGuard<'_, storage::ChangeDetector> {
+    self.config_file.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
   /// 创建新的应用状态
-  fn new() -> Self {
+  /// `clock` 为定时检查、后台任务与事件处理共用的时钟，正式运行时传入 SystemClock
+  fn new(clock: Arc<dyn scheduler::Clock>) -> Self {
     Self {
       config: Mutex::new(AppConfig::default()),
-      timer_running: Mutex::new(false),
+      failures: Mutex::new(scheduler::FailureTracker::default()),
+      page_events: tokio::sync::broadcast::channel(16).0,
+      last_reading: Mutex::new(None),
+      watchdog: Mutex::new(scheduler::WatchdogState::new(clock.now_millis())),
+      login: Mutex::new(scheduler::LoginState::default()),
+      page_load: std::sync::Mutex::new(page_load::PageLoadState::default()),
+      config_file: std::sync::Mutex::new(storage::ChangeDetector::default()),
+      clock,
+      alerts: Mutex::new(alerts::AlertState::default()),
+      reset_at: Mutex::new(None),
+      history: Mutex::new(std::collections::VecDeque::new()),
+      forecast_alerted: Mutex::new(false),
+      reminders: Mutex::new(quota::ReminderState::default()),
+      rules: Mutex::new(rules::RuleState::default()),
+      anomaly_alerted: Mutex::new(false),
+      reschedule: tokio::sync::Notify::new(),
     }
   }
 }
@@ -1064,7 +2700,7 @@ impl AppState {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   // 创建应用状态
-  let app_state = Arc::new(AppState::new());
+  let app_state = Arc::new(AppState::new(Arc::new(scheduler::SystemClock)));
 
   tauri::Builder::default()
     .plugin(tauri_plugin_mcp_bridge::init())
@@ -1088,7 +2724,10 @@ pub fn run() {
     // 注册 Tauri 命令
     .invoke_handler(tauri::generate_handler![
       get_settings,
+      get_effective_settings,
+      get_settings_schema,
       save_settings,
+      patch_settings,
       reset_settings,
       clear_web_caches,
       exit_app,
@@ -1103,6 +2742,20 @@ pub fn run() {
       fetch_usage_from_page,
       get_usage,
       trigger_fetch_usage,
+      check_now,
+      get_forecast,
+      acknowledge_alert,
+      snooze_alerts,
+      unsnooze_alerts,
+      get_snooze_status,
+      list_config_backups,
+      restore_config_backup,
+      export_settings,
+      import_settings,
+      list_profiles,
+      create_profile,
+      switch_profile,
+      delete_profile,
     ])
     .manage(app_state.clone())
     .setup(move |app| {
@@ -1111,20 +2764,8 @@ pub fn run() {
       tauri::async_runtime::spawn(async move {
         // 检查窗口是否已存在
         if app_handle.get_webview_window(MINMAX_WINDOW_LABEL).is_none() {
-          let url = MINMAX_USAGE_URL
-            .parse()
-            .expect("MiniMax URL 解析失败");
-
-          match tauri::WebviewWindowBuilder::new(&app_handle, MINMAX_WINDOW_LABEL, tauri::WebviewUrl::External(url))
-            .title("MiniMax")
-            .inner_size(1100.0, 800.0)
-            .resizable(true)
-            .center()
-            .initialization_script(MINMAX_INIT_SCRIPT)
-            .visible(false) // 静默模式，不显示窗口
-            .skip_taskbar(true) // 不显示在任务栏，完全后台运行
-            .build()
-          {
+          // 静默模式，不显示窗口，也不显示在任务栏，完全后台运行
+          match create_minmax_window(&app_handle, false).await {
             Ok(_) => info!("[setup] MiniMax 窗口已静默创建（后台加载，不显示在任务栏）"),
             Err(e) => error!("[setup] MiniMax 窗口静默创建失败: {}", e),
           }
@@ -1133,19 +2774,96 @@ pub fn run() {
         }
       });
 
+      // 在后端监听使用量事件，用于失败统计与恢复通知
+      let app_state_listener = app_state.clone();
+      app.listen_any("minmax-usage", move |event| {
+        let payload = match serde_json::from_str::<UsageEventPayload>(event.payload()) {
+          Ok(p) => p,
+          Err(e) => {
+            warn!("[usage_event] 事件载荷解析失败: {}", e);
+            return;
+          }
+        };
+        // 转发给正在等待结果的 check_now（没有订阅者时发送失败可忽略）
+        let _ = app_state_listener.page_events.send(PageEvent::Usage(payload.clone()));
+
+        let app_state = app_state_listener.clone();
+        tauri::async_runtime::spawn(async move {
+          handle_usage_event(&app_state, payload).await;
+        });
+      });
+
+      // 监听注入脚本上报的剩余重置时间
+      let app_state_reset = app_state.clone();
+      app.listen_any("minmax-reset-time", move |event| {
+        let payload = match serde_json::from_str::<ResetTimeEventPayload>(event.payload()) {
+          Ok(p) => p,
+          Err(e) => {
+            warn!("[reset_time] 事件载荷解析失败: {}", e);
+            return;
+          }
+        };
+        let app_state = app_state_reset.clone();
+        tauri::async_runtime::spawn(async move {
+          handle_reset_time_event(&app_state, &payload.reset_time).await;
+        });
+      });
+
+      // 监听注入脚本上报的登录失效事件
+      let app_handle = app.handle().clone();
+      let app_state_login = app_state.clone();
+      app.listen_any("minmax-login-required", move |event| {
+        info!("[login_required] 收到登录失效事件: {}", event.payload());
+        match serde_json::from_str::<LoginRequiredEventPayload>(event.payload()) {
+          Ok(payload) => {
+            let _ = app_state_login.page_events.send(PageEvent::LoginRequired(payload));
+          }
+          Err(e) => warn!("[login_required] 事件载荷解析失败: {}", e),
+        }
+        let app = app_handle.clone();
+        let app_state = app_state_login.clone();
+        tauri::async_runtime::spawn(async move {
+          handle_login_required(&app, &app_state).await;
+        });
+      });
+
       // 使用 Tauri 提供的 Tokio runtime 启动异步任务
       let app_handle = app.handle().clone();
       let app_state_clone = app_state.clone();
       tauri::async_runtime::spawn(async move {
+        // 恢复持久化的告警状态，避免重启后重复告警
+        {
+          let alert_state = load_alert_state().await;
+          let mut state = app_state_clone.alerts.lock().await;
+          *state = alert_state;
+        }
+
+        write_settings_schema().await;
+
         if let Ok(config) = get_settings().await {
           // 更新应用状态
+          compile_rules(&app_state_clone, &config).await;
           {
             let mut state = app_state_clone.config.lock().await;
             *state = config;
           } // state 在这里被释放
 
           // 启动定时器
-          start_timer(app_handle, app_state_clone).await;
+          start_timer(app_handle.clone(), app_state_clone.clone()).await;
+
+          // 监听配置文件的外部修改
+          start_config_watcher(app_handle, app_state_clone.clone());
+
+          // 启动数据过期看门狗
+          start_watchdog(app_state_clone.clone());
+
+          // 启动重置前提醒
+          start_reset_reminders(app_state_clone.clone());
+
+          // 启动严重告警升级提醒
+          start_critical_escalation(app_state_clone);
+        } else {
+          error!("[setup] 配置加载失败，后台任务未启动");
         }
       });
 
@@ -1157,15 +2875,8 @@ pub fn run() {
 }
 
 /// 启动定时器
-/// 根据配置启动定时检查任务
+/// 根据配置启动定时检查任务，应用启动时调用一次
 async fn start_timer(app: tauri::AppHandle, app_state: Arc<AppState>) {
-  let mut running = app_state.timer_running.lock().await;
-
-  if *running {
-    info!("[定时任务] 定时器已在运行中，跳过启动");
-    return;
-  }
-
   info!("[定时任务] 正在启动定时器...");
 
   // 获取当前配置
@@ -1175,19 +2886,14 @@ async fn start_timer(app: tauri::AppHandle, app_state: Arc<AppState>) {
   };
 
   let interval_minutes = config.check_interval;
-  let interval_secs = (interval_minutes as u64) * 60;
-
-  info!("[定时任务] 启动定时器，检查间隔: {} 分钟 ({} 秒)", interval_minutes, interval_secs);
 
-  *running = true;
+  info!("[定时任务] 启动定时器，检查间隔: {} 分钟", interval_minutes);
 
   // 使用 Tauri 的 async runtime 启动定时任务
   let app_state_clone = app_state.clone();
   let app_clone = app.clone();
   tauri::async_runtime::spawn(async move {
-    info!("[定时任务] Tauri async 任务已启动，间隔 {} 秒", interval_secs);
-
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+    info!("[定时任务] Tauri async 任务已启动");
 
     // 首次执行
     info!("[定时任务] 执行首次检查...");
@@ -1195,22 +2901,51 @@ async fn start_timer(app: tauri::AppHandle, app_state: Arc<AppState>) {
     info!("[定时任务] 首次检查完成");
 
     let mut tick_count = 0;
+    // 本轮开始等待的时刻，配置变更重新调度时保留，检查执行后清空
+    let mut waiting_since: Option<std::time::Instant> = None;
     loop {
       tick_count += 1;
 
-      // 检查定时器是否停止
-      {
-        let running = app_state_clone.timer_running.lock().await;
-        if !*running {
-          info!("[定时任务] 定时器已停止");
-          break;
+      // 每轮重新读取间隔，连续失败时按指数退避提前重试
+      let (interval_secs, catch_up_policy) = {
+        let state = app_state_clone.config.lock().await;
+        ((state.check_interval.max(1) as u64) * 60, state.catch_up_policy)
+      };
+      let failures = {
+        let tracker = app_state_clone.failures.lock().await;
+        tracker.consecutive_failures
+      };
+      let delay = scheduler::next_check_delay(
+        tokio::time::Duration::from_secs(interval_secs),
+        failures,
+      );
+      // 重新调度时从本轮开始等待的时刻算起，只等待剩余的时间，频繁修改配置不会一直推迟检查
+      let clock = app_state_clone.clock.as_ref();
+      let started = *waiting_since.get_or_insert_with(|| clock.monotonic());
+      let delay = delay.saturating_sub(clock.monotonic().saturating_duration_since(started));
+
+      info!(
+        "[定时任务] 等待第 {} 个 tick，{:.0} 秒后执行（连续失败 {} 次）",
+        tick_count,
+        delay.as_secs_f64(),
+        failures
+      );
+
+      // 等待期间配置发生变化时，按新的间隔重新计算等待时间
+      tokio::select! {
+        _ = scheduler::wait_for_next_check(
+          clock,
+          delay,
+          tokio::time::Duration::from_secs(interval_secs),
+          catch_up_policy,
+        ) => {}
+        _ = app_state_clone.reschedule.notified() => {
+          info!("[定时任务] 配置已变更，按新的间隔重新调度");
+          continue;
         }
       }
 
-      info!("[定时任务] 等待第 {} 个 tick...", tick_count);
-
-      interval.tick().await;
-
+      waiting_since = None;
       info!("[定时任务] 第 {} 个 tick 到达，执行检查", tick_count);
 
       // 执行检查
@@ -1221,3 +2956,250 @@ async fn start_timer(app: tauri::AppHandle, app_state: Arc<AppState>) {
 
   info!("[定时任务] start_timer 函数返回");
 }
+
+/// 看门狗检查周期（秒）
+const WATCHDOG_INTERVAL_SECS: u64 = 60;
+
+/// 启动数据过期看门狗
+/// 定期检查最近一次成功读数的年龄，超过配置上限时发送"监控已失效"告警
+fn start_watchdog(app_state: Arc<AppState>) {
+  info!("[看门狗] 启动，检查周期 {} 秒", WATCHDOG_INTERVAL_SECS);
+
+  tauri::async_runtime::spawn(async move {
+    loop {
+      app_state.clock.sleep(tokio::time::Duration::from_secs(WATCHDOG_INTERVAL_SECS)).await;
+
+      let config = {
+        let state = app_state.config.lock().await;
+        state.clone()
+      };
+      let last_success = {
+        let last = app_state.last_reading.lock().await;
+        last.as_ref().map(|r| r.timestamp)
+      };
+
+      let now = app_state.clock.now_millis();
+      let max_age = tokio::time::Duration::from_secs(config.stale_after_minutes as u64 * 60);
+      let (should_alert, age_millis) = {
+        let mut watchdog = app_state.watchdog.lock().await;
+        (watchdog.check(last_success, now, max_age), watchdog.data_age_millis(last_success, now))
+      };
+
+      if should_alert {
+        send_stale_notification(age_millis / 60_000, &config).await;
+      }
+    }
+  });
+}
+
+/// 重置前提醒的检查周期（秒）
+const REMINDER_INTERVAL_SECS: u64 = 60;
+
+/// 启动重置前提醒任务
+/// 根据推算的重置时间，在重置前提醒，并在使用量仍然较低时提示不要浪费配额
+fn start_reset_reminders(app_state: Arc<AppState>) {
+  info!("[重置提醒] 启动，检查周期 {} 秒", REMINDER_INTERVAL_SECS);
+
+  tauri::async_runtime::spawn(async move {
+    loop {
+      app_state.clock.sleep(tokio::time::Duration::from_secs(REMINDER_INTERVAL_SECS)).await;
+
+      let config = {
+        let state = app_state.config.lock().await;
+        state.clone()
+      };
+      let reset_at = *app_state.reset_at.lock().await;
+      let usage = {
+        let last = app_state.last_reading.lock().await;
+        last.as_ref().map(|r| r.percent)
+      };
+
+      let reminders = {
+        let mut state = app_state.reminders.lock().await;
+        state.evaluate(
+          app_state.clock.now_millis(),
+          reset_at,
+          usage,
+          tokio::time::Duration::from_secs(config.pre_reset_reminder_minutes as u64 * 60),
+          tokio::time::Duration::from_secs(config.unused_quota_nudge_minutes as u64 * 60),
+          config.unused_quota_nudge_percent,
+        )
+      };
+
+      for reminder in reminders {
+        let (title, body) = match reminder {
+          quota::Reminder::PreReset { remaining_millis } => (
+            "MiniMax 配额即将重置",
+            format!("配额将在 {} 后重置。", forecast::format_duration_millis(remaining_millis)),
+          ),
+          quota::Reminder::UnusedQuota { usage, remaining_millis } => (
+            "MiniMax 配额尚未用完",
+            format!(
+              "目前只用了 {:.1}% 的配额，距离重置还有 {}，别浪费已付费的额度。",
+              usage,
+              forecast::format_duration_millis(remaining_millis)
+            ),
+          ),
+        };
+        info!("[重置提醒] {}", body);
+        dispatch_notification(&config, &alerts::all_channels(), title, &body).await;
+      }
+    }
+  });
+}
+
+/// 严重告警升级的检查周期（秒）
+const ESCALATION_INTERVAL_SECS: u64 = 60;
+
+/// 启动严重告警升级提醒任务
+/// 严重告警未确认时按间隔重复提醒，并逐步增加通知渠道
+fn start_critical_escalation(app_state: Arc<AppState>) {
+  info!("[escalation] 启动，检查周期 {} 秒", ESCALATION_INTERVAL_SECS);
+
+  tauri::async_runtime::spawn(async move {
+    loop {
+      app_state.clock.sleep(tokio::time::Duration::from_secs(ESCALATION_INTERVAL_SECS)).await;
+
+      let config = {
+        let state = app_state.config.lock().await;
+        state.clone()
+      };
+
+      let due = {
+        let mut alert_state = app_state.alerts.lock().await;
+        // 静音期间暂停升级，静音结束后继续
+        if alert_state.snooze.is_snoozed(alerts::Severity::Critical, app_state.clock.now_millis()) {
+          continue;
+        }
+        let due = alert_state.due_escalation(
+          app_state.clock.now_millis(),
+          tokio::time::Duration::from_secs(config.critical_repeat_minutes as u64 * 60),
+          &config.critical_escalation,
+        );
+        if due.is_some() {
+          save_alert_state(&alert_state).await;
+        }
+        due.zip(alert_state.escalation.as_ref().map(|e| e.threshold))
+      };
+
+      let Some(((repeat, channels), threshold)) = due else {
+        continue;
+      };
+
+      let usage = {
+        let last = app_state.last_reading.lock().await;
+        last.as_ref().map(|r| r.percent)
+      };
+      let title = format!("MiniMax 严重告警（第 {} 次提醒）", repeat);
+      let body = format!(
+        "当前使用量 {}，已超过严重阈值 {:.1}%。请在应用中确认告警以停止提醒。",
+        usage.map_or("未知".to_string(), |u| format!("{:.1}%", u)),
+        threshold
+      );
+      warn!("[escalation] {}", body);
+      dispatch_notification(&config, &channels, &title, &body).await;
+    }
+  });
+}
+
+/// 配置文件变更的检查周期（秒）
+/// 轮询修改时间与大小而不使用系统文件监听：编辑器常以“写临时文件再重命名”的方式保存，
+/// 监听原文件会在替换后失效，需要监听目录并合并一次保存产生的多个事件；
+/// 单个小文件每 2 秒一次元数据查询的开销可以忽略，也不必引入额外依赖
+const CONFIG_WATCH_INTERVAL_SECS: u64 = 2;
+
+/// 配置文件的修改时间与大小，用于判断文件是否被修改
+async fn config_file_stamp(path: &std::path::Path) -> Option<storage::FileStamp> {
+  let metadata = fs::metadata(path).await.ok()?;
+  Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// 读取配置文件的内容，文件不存在时返回 None
+async fn read_config_file(path: &std::path::Path) -> Result<Option<Vec<u8>>, String> {
+  match fs::read(path).await {
+    Ok(content) => Ok(Some(content)),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+    Err(e) => Err(format!("读取配置文件失败: {}", e)),
+  }
+}
+
+/// 重新加载被外部修改的配置文件
+/// 内容与本应用最近一次写入的相同时不做处理；文件被删除时回退到默认配置；内容无效时返回错误说明，保留当前配置。
+/// 读取文件前先获取配置锁，不会读到其他命令写入一半的修改，也不会用旧内容覆盖刚保存的配置
+async fn reload_config_file(app: &tauri::AppHandle, app_state: &Arc<AppState>) -> Result<(), String> {
+  let mut state = app_state.config.lock().await;
+  let content = read_config_file(&get_config_path()?).await?;
+  if !app_state.config_file().content_changed(content.clone()) {
+    return Ok(());
+  }
+  let file_config = match content {
+    Some(content) => {
+      let content = String::from_utf8(content).map_err(|e| format!("配置文件不是有效的 UTF-8: {}", e))?;
+      parse_config(&content)?.0
+    }
+    None => AppConfig::default(),
+  };
+  let describe = |errors: Vec<validation::FieldError>| {
+    let details: Vec<String> = errors
+      .iter()
+      .map(|e| format!("{}: {}", e.field, e.message))
+      .collect();
+    details.join("；")
+  };
+
+  // 先单独校验文件中的配置：覆盖可能恰好掩盖文件中的无效字段，去掉覆盖后这些字段就会生效
+  validation::validate_config(&file_config).map_err(describe)?;
+  let config = overrides::apply(file_config.clone());
+  validation::validate_config(&config).map_err(|errors| format!("叠加覆盖后的配置无效：{}", describe(errors)))?;
+
+  // 与当前配置相同（如只调整了格式）时不做处理
+  if serde_json::to_value(&*state).ok() == serde_json::to_value(&config).ok() {
+    return Ok(());
+  }
+
+  info!("[config_watcher] 配置文件已在外部修改，重新加载: {:?}", config);
+  apply_config_locked(app, app_state, &mut state, file_config).await;
+  Ok(())
+}
+
+/// 启动配置文件监听任务
+/// 定期检查配置文件的修改时间与大小，外部修改通过校验后立即生效，无效时发送通知并保留当前配置
+fn start_config_watcher(app: tauri::AppHandle, app_state: Arc<AppState>) {
+  info!("[config_watcher] 启动，检查周期 {} 秒", CONFIG_WATCH_INTERVAL_SECS);
+
+  tauri::async_runtime::spawn(async move {
+    let config_path = match get_config_path() {
+      Ok(path) => path,
+      Err(e) => {
+        error!("[config_watcher] 无法确定配置文件路径，停止监听: {}", e);
+        return;
+      }
+    };
+    {
+      let _state = app_state.config.lock().await;
+      let stamp = config_file_stamp(&config_path).await;
+      let content = read_config_file(&config_path).await.unwrap_or_default();
+      *app_state.config_file() = storage::ChangeDetector::new(stamp, content);
+    }
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(CONFIG_WATCH_INTERVAL_SECS));
+
+    loop {
+      interval.tick().await;
+
+      let stamp = config_file_stamp(&config_path).await;
+      if !app_state.config_file().stamp_changed(stamp) {
+        continue;
+      }
+
+      if let Err(e) = reload_config_file(&app, &app_state).await {
+        warn!("[config_watcher] 配置文件无效，已忽略本次修改: {}", e);
+        let config = {
+          let state = app_state.config.lock().await;
+          state.clone()
+        };
+        let body = format!("配置文件的修改未生效，继续使用当前配置。{}", e);
+        dispatch_notification(&config, &[alerts::NotificationChannel::System], "MiniMax 配置文件无效", &body).await;
+      }
+    }
+  });
+}
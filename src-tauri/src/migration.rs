@@ -0,0 +1,164 @@
+//! 配置文件版本迁移
+//! 配置文件带有 `version` 字段，加载时按顺序执行迁移步骤，把旧版配置逐步升级到当前版本
+
+use serde_json::{Map, Value};
+
+/// 当前配置版本
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// 单个迁移步骤：把第 n 版配置升级到第 n + 1 版
+type MigrationStep = fn(&mut Map<String, Value>);
+
+/// 迁移步骤列表，第 i 项把第 i 版升级到第 i + 1 版
+/// 新增步骤时同步增加 CURRENT_CONFIG_VERSION
+const MIGRATIONS: &[MigrationStep] = &[migrate_v0_to_v1];
+
+/// 迁移结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationOutcome {
+  /// 迁移前的版本
+  pub from_version: u32,
+  /// 迁移后的版本
+  pub to_version: u32,
+}
+
+impl MigrationOutcome {
+  /// 是否执行了迁移
+  pub fn migrated(&self) -> bool {
+    self.from_version != self.to_version
+  }
+}
+
+/// 读取配置版本，没有 version 字段的旧配置视为第 0 版
+pub fn config_version(value: &Value) -> u32 {
+  value
+    .get("version")
+    .and_then(Value::as_u64)
+    .map_or(0, |version| version as u32)
+}
+
+/// 检查现有配置文件能否被覆盖
+/// 由更新的程序写入（版本高于当前版本）的文件可能包含当前版本不认识的字段，覆盖会丢失这些设置，因此拒绝写入
+pub fn ensure_writable(existing: &Value) -> Result<(), String> {
+  let version = config_version(existing);
+  if version > CURRENT_CONFIG_VERSION {
+    return Err(format!(
+      "配置文件由更新版本的程序写入（第 {} 版，当前支持第 {} 版），为避免丢失其中的设置已拒绝覆盖，请升级后再修改配置",
+      version, CURRENT_CONFIG_VERSION
+    ));
+  }
+  Ok(())
+}
+
+/// 将配置升级到当前版本
+/// 版本高于当前版本（由更新的程序写入）时不做修改
+pub fn migrate_config(value: &mut Value) -> Result<MigrationOutcome, String> {
+  let from_version = config_version(value);
+  let obj = value
+    .as_object_mut()
+    .ok_or_else(|| "配置文件格式错误：顶层必须是对象".to_string())?;
+
+  if from_version > CURRENT_CONFIG_VERSION {
+    log::warn!(
+      "配置版本 {} 高于当前支持的版本 {}，按当前版本读取，未知字段将被忽略且不会覆盖该文件",
+      from_version,
+      CURRENT_CONFIG_VERSION
+    );
+    return Ok(MigrationOutcome { from_version, to_version: from_version });
+  }
+
+  for (version, step) in MIGRATIONS.iter().enumerate().skip(from_version as usize) {
+    log::info!("配置迁移: 第 {} 版 -> 第 {} 版", version, version + 1);
+    step(obj);
+  }
+  obj.insert("version".to_string(), Value::from(CURRENT_CONFIG_VERSION));
+
+  Ok(MigrationOutcome {
+    from_version,
+    to_version: CURRENT_CONFIG_VERSION,
+  })
+}
+
+/// 第 0 版 -> 第 1 版：旧版单一阈值 warningThreshold 迁移为多级阈值 thresholdTiers
+fn migrate_v0_to_v1(obj: &mut Map<String, Value>) {
+  if obj.contains_key("thresholdTiers") {
+    obj.remove("warningThreshold");
+    return;
+  }
+  let Some(legacy) = obj.remove("warningThreshold") else {
+    return;
+  };
+
+  let threshold = legacy.as_f64().unwrap_or(90.0);
+  let tiers = vec![crate::alerts::ThresholdTier::new(
+    threshold,
+    crate::alerts::Severity::Warning,
+    crate::alerts::all_channels(),
+  )];
+  obj.insert(
+    "thresholdTiers".to_string(),
+    serde_json::to_value(tiers).unwrap_or_default(),
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn missing_version_is_version_zero() {
+    assert_eq!(config_version(&json!({})), 0);
+    assert_eq!(config_version(&json!({ "version": 1 })), 1);
+  }
+
+  #[test]
+  fn legacy_threshold_becomes_a_warning_tier() {
+    let mut value = json!({ "warningThreshold": 75.0, "checkInterval": 5 });
+    let outcome = migrate_config(&mut value).unwrap();
+    assert!(outcome.migrated());
+    assert_eq!(outcome.from_version, 0);
+    assert_eq!(value["version"], json!(CURRENT_CONFIG_VERSION));
+    assert!(value.get("warningThreshold").is_none());
+    assert_eq!(value["thresholdTiers"][0]["threshold"], json!(75.0));
+    assert_eq!(value["thresholdTiers"][0]["severity"], json!("warning"));
+    assert_eq!(value["checkInterval"], json!(5));
+  }
+
+  #[test]
+  fn existing_tiers_win_over_legacy_threshold() {
+    let mut value = json!({ "warningThreshold": 75.0, "thresholdTiers": [] });
+    migrate_config(&mut value).unwrap();
+    assert!(value.get("warningThreshold").is_none());
+    assert_eq!(value["thresholdTiers"], json!([]));
+  }
+
+  #[test]
+  fn current_version_is_left_alone() {
+    let mut value = json!({ "version": CURRENT_CONFIG_VERSION, "warningThreshold": 75.0 });
+    let outcome = migrate_config(&mut value).unwrap();
+    assert!(!outcome.migrated());
+    assert_eq!(value["warningThreshold"], json!(75.0));
+  }
+
+  #[test]
+  fn newer_version_is_not_touched() {
+    let newer = CURRENT_CONFIG_VERSION + 1;
+    let mut value = json!({ "version": newer, "futureField": true });
+    let outcome = migrate_config(&mut value).unwrap();
+    assert!(!outcome.migrated());
+    assert_eq!(value, json!({ "version": newer, "futureField": true }));
+  }
+
+  #[test]
+  fn non_object_config_is_rejected() {
+    assert!(migrate_config(&mut json!([1, 2])).is_err());
+  }
+
+  #[test]
+  fn newer_version_file_is_not_writable() {
+    assert!(ensure_writable(&json!({})).is_ok());
+    assert!(ensure_writable(&json!({ "version": CURRENT_CONFIG_VERSION })).is_ok());
+    assert!(ensure_writable(&json!({ "version": CURRENT_CONFIG_VERSION + 1 })).is_err());
+  }
+}
@@ -0,0 +1,435 @@
+//! 配置覆盖
+//! 受管机器可以通过环境变量或命令行参数覆盖任意配置字段，优先级：命令行参数 > 环境变量 > 配置文件 > 默认值
+//! - 环境变量：`MINMAX_HELPER_` 加字段名的大写下划线形式，如 `MINMAX_HELPER_CHECK_INTERVAL=5`
+//! - 命令行参数：字段名的短横线形式，如 `--check-interval 5` 或 `--check-interval=5`；布尔字段可省略取值
+//!
+//! 字段类型取自配置的 JSON Schema：字符串字段（包括可为空的字符串）直接使用原始文本，其他字段按 JSON 解析（如 `--threshold-tiers '[...]'`）；
+//! 每个覆盖值与配置文件一样经过校验，无效的覆盖（如 `MINMAX_HELPER_CHECK_INTERVAL=0`）被忽略；
+//! 被覆盖的字段不会写回配置文件，去掉覆盖后恢复为文件中的取值
+
+use crate::{validation, AppConfig};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// 环境变量前缀
+const ENV_PREFIX: &str = "MINMAX_HELPER_";
+/// 不允许覆盖的字段（由应用维护）
+const RESERVED_FIELDS: &[&str] = &["version"];
+
+/// 配置取值的来源
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SettingSource {
+  /// 默认值
+  Default,
+  /// 配置文件
+  File,
+  /// 环境变量
+  Environment,
+  /// 命令行参数
+  CommandLine,
+}
+
+/// 单个字段的覆盖
+#[derive(Clone, Debug)]
+pub struct Override {
+  /// 字段名（与配置文件中的 JSON 键一致）
+  pub field: String,
+  /// 覆盖后的取值
+  pub value: Value,
+  /// 来源（环境变量或命令行参数）
+  pub source: SettingSource,
+}
+
+/// 生效的配置及各字段的来源
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct EffectiveSettings {
+  /// 生效的配置
+  #[serde(rename = "settings")]
+  pub settings: AppConfig,
+  /// 各字段的来源
+  #[serde(rename = "sources")]
+  pub sources: BTreeMap<String, SettingSource>,
+}
+
+/// 驼峰字段名转为环境变量名，如 checkInterval -> MINMAX_HELPER_CHECK_INTERVAL
+fn env_name(field: &str) -> String {
+  let mut name = String::from(ENV_PREFIX);
+  for c in field.chars() {
+    if c.is_ascii_uppercase() {
+      name.push('_');
+    }
+    name.push(c.to_ascii_uppercase());
+  }
+  name
+}
+
+/// 驼峰字段名转为命令行参数，如 checkInterval -> --check-interval
+fn flag_name(field: &str) -> String {
+  let mut name = String::from("--");
+  for c in field.chars() {
+    if c.is_ascii_uppercase() {
+      name.push('-');
+    }
+    name.push(c.to_ascii_lowercase());
+  }
+  name
+}
+
+/// 默认配置的各字段
+fn default_fields() -> Map<String, Value> {
+  match serde_json::to_value(AppConfig::default()) {
+    Ok(Value::Object(fields)) => fields,
+    _ => Map::new(),
+  }
+}
+
+/// 可覆盖的字段及其在 JSON Schema 中的类型，如 `["string", "null"]`；引用其他定义的字段（如枚举）类型为空
+/// 按 Schema 而不是默认值判断类型：可为空的字段默认值是 null，看不出实际类型
+fn field_types() -> Vec<(String, Vec<String>)> {
+  let schema = crate::schema::settings_schema();
+  let Some(properties) = schema["properties"].as_object() else {
+    return Vec::new();
+  };
+  properties
+    .iter()
+    .filter(|(field, _)| !RESERVED_FIELDS.contains(&field.as_str()))
+    .map(|(field, property)| {
+      let types = match &property["type"] {
+        Value::String(kind) => vec![kind.clone()],
+        Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        _ => Vec::new(),
+      };
+      (field.clone(), types)
+    })
+    .collect()
+}
+
+/// 按字段类型解析原始文本：字符串字段直接使用文本，其他字段按 JSON 解析，解析失败时当作字符串
+fn parse_value(raw: &str, types: &[String]) -> Value {
+  if types.iter().any(|kind| kind == "string") {
+    return Value::String(raw.to_string());
+  }
+  serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+/// 从环境变量与命令行参数中收集覆盖；同一字段命令行参数优先
+pub fn collect(
+  env: impl IntoIterator<Item = (String, String)>,
+  args: impl IntoIterator<Item = String>,
+) -> Vec<Override> {
+  let fields = field_types();
+  let mut overrides: BTreeMap<String, Override> = BTreeMap::new();
+
+  for (name, raw) in env {
+    if let Some((field, types)) = fields.iter().find(|(field, _)| env_name(field) == name) {
+      overrides.insert(
+        field.to_string(),
+        Override {
+          field: field.to_string(),
+          value: parse_value(&raw, types),
+          source: SettingSource::Environment,
+        },
+      );
+    }
+  }
+
+  let args: Vec<String> = args.into_iter().collect();
+  let mut i = 0;
+  while i < args.len() {
+    let arg = &args[i];
+    i += 1;
+    let (flag, inline) = match arg.split_once('=') {
+      Some((flag, value)) => (flag, Some(value.to_string())),
+      None => (arg.as_str(), None),
+    };
+    let Some((field, types)) = fields.iter().find(|(field, _)| flag_name(field) == flag) else {
+      continue;
+    };
+
+    let raw = match inline {
+      Some(raw) => raw,
+      // 布尔字段单独出现时表示 true
+      None if types.iter().any(|kind| kind == "boolean") && args.get(i).map_or(true, |next| next.starts_with("--")) => "true".to_string(),
+      None => match args.get(i) {
+        Some(raw) => {
+          i += 1;
+          raw.clone()
+        }
+        None => {
+          log::warn!("命令行参数 {} 缺少取值，已忽略", flag);
+          continue;
+        }
+      },
+    };
+    overrides.insert(
+      field.to_string(),
+      Override {
+        field: field.to_string(),
+        value: parse_value(&raw, types),
+        source: SettingSource::CommandLine,
+      },
+    );
+  }
+
+  overrides.into_values().collect()
+}
+
+/// 当前进程的覆盖（启动时从环境变量与命令行参数中读取一次）
+pub fn overrides() -> &'static [Override] {
+  static OVERRIDES: OnceLock<Vec<Override>> = OnceLock::new();
+  OVERRIDES.get_or_init(|| {
+    let env = std::env::vars_os().filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)));
+    let args = std::env::args_os().skip(1).map(|arg| arg.to_string_lossy().into_owned());
+    let overrides = collect(env, args);
+    for item in &overrides {
+      log::info!("配置字段 {} 被覆盖为 {}（来源: {:?}）", item.field, item.value, item.source);
+    }
+    overrides
+  })
+}
+
+/// 检查覆盖后的配置中该字段是否通过校验；其他字段原有的错误不影响该覆盖
+fn validate_field(config: &AppConfig, field: &str) -> Result<(), String> {
+  let errors: Vec<String> = validation::validate_config(config)
+    .err()
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|e| validation::split_field(&e.field).0 == field)
+    .map(|e| format!("{}: {}", e.field, e.message))
+    .collect();
+  if errors.is_empty() {
+    Ok(())
+  } else {
+    Err(errors.join("；"))
+  }
+}
+
+/// 把覆盖应用到配置上，同时返回实际生效的覆盖
+/// 逐个字段应用，某个覆盖的取值无法解析或未通过校验时记录日志并跳过，不影响其他字段
+fn apply_each(config: AppConfig, overrides: &[Override]) -> (AppConfig, Vec<&Override>) {
+  let mut current = config;
+  let mut applied = Vec::new();
+  for item in overrides {
+    let mut value = match serde_json::to_value(&current) {
+      Ok(value) => value,
+      Err(_) => break,
+    };
+    if let Value::Object(fields) = &mut value {
+      fields.insert(item.field.clone(), item.value.clone());
+    }
+    let result = serde_json::from_value::<AppConfig>(value)
+      .map_err(|e| e.to_string())
+      .and_then(|config| validate_field(&config, &item.field).map(|_| config));
+    match result {
+      Ok(config) => {
+        current = config;
+        applied.push(item);
+      }
+      Err(e) => log::warn!("配置字段 {} 的覆盖值 {} 无效，已忽略: {}", item.field, item.value, e),
+    }
+  }
+  (current, applied)
+}
+
+/// 把覆盖应用到配置上
+pub fn apply_overrides(config: AppConfig, overrides: &[Override]) -> AppConfig {
+  apply_each(config, overrides).0
+}
+
+/// 把当前进程的覆盖应用到配置上
+pub fn apply(config: AppConfig) -> AppConfig {
+  apply_overrides(config, overrides())
+}
+
+/// 写回配置文件前去掉覆盖的影响：被覆盖的字段恢复为原文件中的取值，原文件中没有时删除
+pub fn restore_file_values(settings: &mut Map<String, Value>, file: Option<&Map<String, Value>>, overrides: &[Override]) {
+  for item in overrides {
+    match file.and_then(|file| file.get(&item.field)) {
+      Some(value) => {
+        settings.insert(item.field.clone(), value.clone());
+      }
+      None => {
+        settings.remove(&item.field);
+      }
+    }
+  }
+}
+
+/// 去掉覆盖的影响，得到配置文件层面的配置
+/// 被覆盖的字段恢复为原文件中的取值，原文件中没有时使用默认值；保存配置方案时使用，受管机器的覆盖不会随方案带到其他机器
+pub fn file_config(config: &AppConfig, file: Option<&Map<String, Value>>, overrides: &[Override]) -> Result<AppConfig, String> {
+  let mut value = serde_json::to_value(config).map_err(|e| format!("配置序列化失败: {}", e))?;
+  if let Value::Object(settings) = &mut value {
+    restore_file_values(settings, file, overrides);
+  }
+  serde_json::from_value(value).map_err(|e| format!("配置文件中被覆盖的字段无效: {}", e))
+}
+
+/// 计算生效的配置及各字段的来源
+/// `file_config` 为配置文件中的配置，`file_fields` 为配置文件中实际出现的字段（迁移后）
+pub fn effective(file_config: AppConfig, file_fields: &Map<String, Value>, overrides: &[Override]) -> EffectiveSettings {
+  let mut sources: BTreeMap<String, SettingSource> = default_fields()
+    .keys()
+    .map(|field| {
+      let source = if file_fields.contains_key(field) {
+        SettingSource::File
+      } else {
+        SettingSource::Default
+      };
+      (field.clone(), source)
+    })
+    .collect();
+
+  // 只有真正生效的覆盖才标记来源
+  let (settings, applied) = apply_each(file_config, overrides);
+  for item in applied {
+    sources.insert(item.field.clone(), item.source);
+  }
+
+  EffectiveSettings { settings, sources }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn env(list: &[(&str, &str)]) -> Vec<(String, String)> {
+    list.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+  }
+
+  fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|arg| arg.to_string()).collect()
+  }
+
+  fn values(overrides: &[Override]) -> Vec<(&str, &Value, SettingSource)> {
+    overrides.iter().map(|o| (o.field.as_str(), &o.value, o.source)).collect()
+  }
+
+  #[test]
+  fn field_names_map_to_env_and_flags() {
+    assert_eq!(env_name("checkInterval"), "MINMAX_HELPER_CHECK_INTERVAL");
+    assert_eq!(env_name("wechatWorkWebhookUrl"), "MINMAX_HELPER_WECHAT_WORK_WEBHOOK_URL");
+    assert_eq!(flag_name("checkInterval"), "--check-interval");
+  }
+
+  #[test]
+  fn collects_env_and_args_with_command_line_precedence() {
+    let overrides = collect(
+      env(&[("MINMAX_HELPER_CHECK_INTERVAL", "5"), ("MINMAX_HELPER_LANGUAGE", "en"), ("PATH", "/bin")]),
+      args(&["--check-interval", "7", "--unknown", "x"]),
+    );
+    assert_eq!(
+      values(&overrides),
+      vec![
+        ("checkInterval", &json!(7), SettingSource::CommandLine),
+        ("language", &json!("en"), SettingSource::Environment),
+      ]
+    );
+  }
+
+  #[test]
+  fn parses_inline_values_and_bare_booleans() {
+    let overrides = collect(Vec::new(), args(&["--notify-recovery", "--language=en", "--check-interval"]));
+    assert_eq!(
+      values(&overrides),
+      vec![
+        ("language", &json!("en"), SettingSource::CommandLine),
+        ("notifyRecovery", &json!(true), SettingSource::CommandLine),
+      ]
+    );
+  }
+
+  #[test]
+  fn nullable_string_fields_take_raw_text() {
+    let overrides = collect(env(&[("MINMAX_HELPER_ACTIVE_PROFILE", "123")]), args(&["--active-profile=true"]));
+    assert_eq!(values(&overrides), vec![("activeProfile", &json!("true"), SettingSource::CommandLine)]);
+    let overrides = collect(env(&[("MINMAX_HELPER_ACTIVE_PROFILE", "123")]), Vec::new());
+    assert_eq!(apply_overrides(AppConfig::default(), &overrides).active_profile.as_deref(), Some("123"));
+  }
+
+  #[test]
+  fn version_cannot_be_overridden() {
+    assert!(collect(env(&[("MINMAX_HELPER_VERSION", "9")]), args(&["--version", "9"])).is_empty());
+  }
+
+  #[test]
+  fn invalid_overrides_are_skipped() {
+    let overrides = collect(
+      env(&[
+        ("MINMAX_HELPER_CHECK_INTERVAL", "0"),
+        ("MINMAX_HELPER_LANGUAGE", "en"),
+        ("MINMAX_HELPER_PAGE_LOAD_TIMEOUT_SECS", "soon"),
+      ]),
+      Vec::new(),
+    );
+    let (config, applied) = apply_each(AppConfig::default(), &overrides);
+    assert_eq!(config.check_interval, AppConfig::default().check_interval);
+    assert_eq!(config.page_load_timeout_secs, AppConfig::default().page_load_timeout_secs);
+    assert_eq!(config.language, "en");
+    assert_eq!(applied.iter().map(|o| o.field.as_str()).collect::<Vec<_>>(), vec!["language"]);
+  }
+
+  #[test]
+  fn override_is_not_blamed_for_other_invalid_fields() {
+    let file = AppConfig {
+      check_interval: 0,
+      ..AppConfig::default()
+    };
+    let overrides = collect(env(&[("MINMAX_HELPER_LANGUAGE", "en")]), Vec::new());
+    assert_eq!(apply_overrides(file, &overrides).language, "en");
+  }
+
+  #[test]
+  fn restore_file_values_undoes_overrides() {
+    let overrides = collect(
+      env(&[("MINMAX_HELPER_CHECK_INTERVAL", "5"), ("MINMAX_HELPER_LANGUAGE", "en")]),
+      Vec::new(),
+    );
+    let mut settings = json!({ "checkInterval": 5, "language": "en", "notifyRecovery": true });
+    let file = json!({ "checkInterval": 30 });
+    restore_file_values(settings.as_object_mut().unwrap(), file.as_object(), &overrides);
+    assert_eq!(settings, json!({ "checkInterval": 30, "notifyRecovery": true }));
+  }
+
+  #[test]
+  fn file_config_drops_overridden_values() {
+    let overrides = collect(
+      env(&[("MINMAX_HELPER_CHECK_INTERVAL", "5"), ("MINMAX_HELPER_LANGUAGE", "en")]),
+      Vec::new(),
+    );
+    let file = json!({ "checkInterval": 15, "notifyRecovery": false });
+    let effective = apply_overrides(
+      AppConfig {
+        check_interval: 15,
+        notify_recovery: false,
+        ..AppConfig::default()
+      },
+      &overrides,
+    );
+    assert_eq!((effective.check_interval, effective.language.as_str()), (5, "en"));
+
+    let config = file_config(&effective, file.as_object(), &overrides).unwrap();
+    assert_eq!(config.check_interval, 15);
+    assert_eq!(config.language, AppConfig::default().language);
+    assert!(!config.notify_recovery);
+  }
+
+  #[test]
+  fn effective_reports_sources() {
+    let overrides = collect(env(&[("MINMAX_HELPER_LANGUAGE", "en")]), args(&["--check-interval", "0"]));
+    let file_fields = json!({ "checkInterval": 30 });
+    let file_config = AppConfig {
+      check_interval: 30,
+      ..AppConfig::default()
+    };
+    let effective = effective(file_config, file_fields.as_object().unwrap(), &overrides);
+    assert_eq!(effective.settings.check_interval, 30);
+    assert_eq!(effective.settings.language, "en");
+    assert_eq!(effective.sources["checkInterval"], SettingSource::File);
+    assert_eq!(effective.sources["language"], SettingSource::Environment);
+    assert_eq!(effective.sources["notifyRecovery"], SettingSource::Default);
+  }
+}
@@ -0,0 +1,171 @@
+//! MiniMax 窗口的页面加载跟踪
+//! 每次刷新或重建窗口时分配递增的加载序号，根据页面加载回调判断最近一次加载是否完成，
+//! 并对加载超时后的窗口重建做指数退避，避免网络断开时反复销毁重建窗口
+
+use std::time::Duration;
+
+/// 连续重建的退避基数（秒）
+const RECREATE_BACKOFF_BASE_SECS: u64 = 60;
+/// 连续重建的退避上限（秒）
+const RECREATE_BACKOFF_MAX_SECS: u64 = 3600;
+
+/// 判断一次上报是否属于从加载 `sequence` 开始的检查
+/// 本次刷新及之后重建窗口的加载都属于本次检查；更早加载迟到的上报（包括后端发出的加载超时）
+/// 以及没有序号的其他触发失败都不属于本次检查
+pub fn belongs_to_check(seq: Option<u64>, sequence: u64) -> bool {
+  seq.is_some_and(|seq| seq >= sequence)
+}
+
+/// 页面加载状态
+/// 记录 MiniMax 窗口的创建时间、尚未完成的页面加载以及连续重建的次数
+#[derive(Debug, Default)]
+pub struct PageLoadState {
+  /// 页面加载序号，每次刷新或重建窗口时递增
+  sequence: u64,
+  /// 正在等待完成的加载序号
+  pending: Option<u64>,
+  /// 最近一次 Started 事件对应的加载序号
+  started: Option<u64>,
+  /// 当前 MiniMax 窗口的创建时间（Unix 毫秒）
+  pub window_created_at: Option<u64>,
+  /// 加载超时后连续重建窗口的次数，页面加载完成后清零
+  consecutive_recreates: u32,
+  /// 最近一次重建窗口的时间（Unix 毫秒）
+  last_recreate_at: Option<u64>,
+}
+
+impl PageLoadState {
+  /// 开始跟踪一次页面加载，返回本次加载序号
+  pub fn begin(&mut self) -> u64 {
+    self.sequence += 1;
+    self.pending = Some(self.sequence);
+    self.sequence
+  }
+
+  /// 收到 Started 事件：此后开始的加载属于最近一次 begin
+  pub fn on_started(&mut self) {
+    self.started = self.pending;
+  }
+
+  /// 收到 Finished 事件
+  /// 只有在最近一次 begin 之后开始的加载完成时才清空等待状态，
+  /// 刷新前旧页面迟到的 Finished 不会让新的加载被误认为已完成；返回是否清空
+  pub fn on_finished(&mut self) -> bool {
+    if self.pending.is_none() || self.started != self.pending {
+      return false;
+    }
+    self.pending = None;
+    self.consecutive_recreates = 0;
+    true
+  }
+
+  /// 最近一次开始的加载序号
+  pub fn latest(&self) -> u64 {
+    self.sequence
+  }
+
+  /// 指定序号的加载是否仍未完成（期间没有开始新的加载）
+  pub fn is_pending(&self, sequence: u64) -> bool {
+    self.pending == Some(sequence)
+  }
+
+  /// 下一次重建前需要等待的时间：从 RECREATE_BACKOFF_BASE_SECS 开始按 2 的幂增长，上限 RECREATE_BACKOFF_MAX_SECS
+  fn recreate_backoff(&self) -> Duration {
+    let exponent = self.consecutive_recreates.saturating_sub(1).min(16);
+    Duration::from_secs((RECREATE_BACKOFF_BASE_SECS << exponent).min(RECREATE_BACKOFF_MAX_SECS))
+  }
+
+  /// 申请重建窗口
+  /// 允许时记录本次重建并返回 Ok；仍在退避期内时返回 Err(剩余等待时间)
+  pub fn try_recreate(&mut self, now: u64) -> Result<(), Duration> {
+    if self.consecutive_recreates > 0 {
+      let backoff = self.recreate_backoff();
+      let elapsed = Duration::from_millis(self.last_recreate_at.map_or(u64::MAX, |at| now.saturating_sub(at)));
+      if elapsed < backoff {
+        return Err(backoff - elapsed);
+      }
+    }
+    self.consecutive_recreates = self.consecutive_recreates.saturating_add(1);
+    self.last_recreate_at = Some(now);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn finished_clears_matching_load() {
+    let mut state = PageLoadState::default();
+    let sequence = state.begin();
+    state.on_started();
+    assert!(state.is_pending(sequence));
+    assert!(state.on_finished());
+    assert!(!state.is_pending(sequence));
+  }
+
+  #[test]
+  fn late_finished_from_previous_load_is_ignored() {
+    let mut state = PageLoadState::default();
+    state.begin();
+    state.on_started();
+
+    // 旧页面还没加载完就开始了新的加载
+    let second = state.begin();
+    assert!(!state.on_finished());
+    assert!(state.is_pending(second));
+
+    state.on_started();
+    assert!(state.on_finished());
+    assert!(!state.is_pending(second));
+  }
+
+  #[test]
+  fn finished_without_pending_load_is_ignored() {
+    let mut state = PageLoadState::default();
+    state.on_started();
+    assert!(!state.on_finished());
+  }
+
+  #[test]
+  fn reports_are_matched_to_the_check_by_sequence() {
+    assert!(belongs_to_check(Some(5), 5));
+    // 加载超时后重建窗口，新窗口的上报仍属于本次检查
+    assert!(belongs_to_check(Some(6), 5));
+    // 上一次加载迟到的超时错误
+    assert!(!belongs_to_check(Some(4), 5));
+    assert!(!belongs_to_check(None, 5));
+  }
+
+  #[test]
+  fn recreates_back_off_exponentially() {
+    let mut state = PageLoadState::default();
+    assert!(state.try_recreate(0).is_ok());
+    assert_eq!(state.try_recreate(30_000), Err(Duration::from_secs(30)));
+    assert!(state.try_recreate(60_000).is_ok());
+    assert_eq!(state.try_recreate(60_000), Err(Duration::from_secs(120)));
+    assert!(state.try_recreate(180_000).is_ok());
+  }
+
+  #[test]
+  fn recreate_backoff_is_capped() {
+    let mut state = PageLoadState::default();
+    let mut now = 0;
+    for _ in 0..20 {
+      now += RECREATE_BACKOFF_MAX_SECS * 1000;
+      assert!(state.try_recreate(now).is_ok());
+    }
+    assert_eq!(state.recreate_backoff(), Duration::from_secs(RECREATE_BACKOFF_MAX_SECS));
+  }
+
+  #[test]
+  fn successful_load_resets_recreate_backoff() {
+    let mut state = PageLoadState::default();
+    assert!(state.try_recreate(0).is_ok());
+    state.begin();
+    state.on_started();
+    state.on_finished();
+    assert!(state.try_recreate(1).is_ok());
+  }
+}
@@ -0,0 +1,84 @@
+//! 配置局部更新
+//! 按 JSON Merge Patch（RFC 7386）把补丁合并到当前配置；合并前检查补丁中的顶层字段，
+//! 配置中不存在的字段（如拼写错误的 `langauge`）返回校验错误，而不是被静默忽略
+
+use crate::validation::FieldError;
+use crate::AppConfig;
+use serde_json::{Map, Value};
+
+/// 检查补丁中的顶层字段是否都是配置字段
+/// 以默认配置序列化后的字段为准，全部存在时返回 Ok，否则返回每个未知字段的错误
+pub fn check_fields(patch: &Map<String, Value>) -> Result<(), Vec<FieldError>> {
+  let known = match serde_json::to_value(AppConfig::default()) {
+    Ok(Value::Object(known)) => known,
+    _ => Map::new(),
+  };
+  let errors: Vec<FieldError> = patch
+    .keys()
+    .filter(|key| !known.contains_key(*key))
+    .map(|key| FieldError::new(key.clone(), "unknownField", format!("未知的配置字段: {}", key)))
+    .collect();
+  if errors.is_empty() {
+    Ok(())
+  } else {
+    Err(errors)
+  }
+}
+
+/// 按 JSON Merge Patch（RFC 7386）规则合并：对象逐键合并，null 删除对应键，其他值整体替换
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+  let Value::Object(patch) = patch else {
+    *target = patch.clone();
+    return;
+  };
+  if !target.is_object() {
+    *target = Value::Object(Map::new());
+  }
+  if let Value::Object(target) = target {
+    for (key, value) in patch {
+      if value.is_null() {
+        target.remove(key);
+      } else {
+        merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn merged(target: Value, patch: Value) -> Value {
+    let mut target = target;
+    merge_patch(&mut target, &patch);
+    target
+  }
+
+  #[test]
+  fn merge_follows_rfc_7386() {
+    assert_eq!(merged(json!({ "a": "b" }), json!({ "a": "c" })), json!({ "a": "c" }));
+    assert_eq!(merged(json!({ "a": "b" }), json!({ "b": "c" })), json!({ "a": "b", "b": "c" }));
+    assert_eq!(merged(json!({ "a": "b", "b": "c" }), json!({ "a": null })), json!({ "b": "c" }));
+    assert_eq!(merged(json!({ "a": ["b"] }), json!({ "a": "c" })), json!({ "a": "c" }));
+    assert_eq!(merged(json!({ "a": { "b": "c" } }), json!({ "a": { "b": "d", "c": null } })), json!({ "a": { "b": "d" } }));
+    assert_eq!(merged(json!({ "a": [{ "b": "c" }] }), json!({ "a": [1] })), json!({ "a": [1] }));
+    assert_eq!(merged(json!(["a", "b"]), json!({ "a": "b" })), json!({ "a": "b" }));
+    assert_eq!(merged(json!({}), json!({ "a": { "bb": { "ccc": null } } })), json!({ "a": { "bb": {} } }));
+  }
+
+  #[test]
+  fn known_fields_pass() {
+    let patch = json!({ "language": "en", "checkInterval": 5, "wechatWorkWebhookUrl": null });
+    assert!(check_fields(patch.as_object().unwrap()).is_ok());
+  }
+
+  #[test]
+  fn unknown_fields_are_rejected() {
+    let patch = json!({ "langauge": "en", "checkInterval": 5, "extra": null });
+    let errors = check_fields(patch.as_object().unwrap()).unwrap_err();
+    let fields: Vec<(&str, &str)> = errors.iter().map(|e| (e.field.as_str(), e.code)).collect();
+    assert_eq!(fields, vec![("extra", "unknownField"), ("langauge", "unknownField")]);
+  }
+}
@@ -0,0 +1,336 @@
+//! 应用目录
+//! 按以下优先级确定配置、数据与状态目录：
+//! 1. 命令行参数 `--config-dir <目录>`
+//! 2. 环境变量 `MINMAX_HELPER_HOME`
+//! 3. 便携模式：可执行文件旁存在 `portable` 标记文件，或设置了环境变量 `MINMAX_HELPER_PORTABLE`，
+//!    使用可执行文件旁的 `minmax-helper-data` 目录
+//! 4. Linux 上遵循 XDG 规范（`$XDG_CONFIG_HOME`、`$XDG_DATA_HOME`、`$XDG_STATE_HOME`），
+//!    并从旧的 `~/.minmax-helper` 迁移已有文件
+//! 5. 其他平台使用 `~/.minmax-helper`
+//!
+//! 前三种方式把所有文件放在同一个目录下；任何路径错误都以 Err 返回
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 应用目录名
+const APP_DIR_NAME: &str = "minmax-helper";
+/// 旧版目录名（位于主目录下）
+const LEGACY_DIR_NAME: &str = ".minmax-helper";
+/// 便携模式的目录名（位于可执行文件旁）
+const PORTABLE_DIR_NAME: &str = "minmax-helper-data";
+/// 便携模式标记文件名
+const PORTABLE_MARKER: &str = "portable";
+
+/// 目录覆盖的命令行参数
+const CONFIG_DIR_ARG: &str = "--config-dir";
+/// 目录覆盖的环境变量
+const HOME_ENV: &str = "MINMAX_HELPER_HOME";
+/// 便携模式的环境变量
+const PORTABLE_ENV: &str = "MINMAX_HELPER_PORTABLE";
+
+/// 目录的来源
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PathSource {
+  /// 命令行参数 --config-dir
+  CommandLine,
+  /// 环境变量 MINMAX_HELPER_HOME
+  Environment,
+  /// 便携模式
+  Portable,
+  /// XDG 目录
+  Xdg,
+  /// 主目录下的 .minmax-helper
+  Home,
+}
+
+/// 应用使用的目录
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct AppPaths {
+  /// 配置目录（config.json）
+  #[serde(rename = "configDir")]
+  pub config_dir: PathBuf,
+  /// 数据目录（配置备份）
+  #[serde(rename = "dataDir")]
+  pub data_dir: PathBuf,
+  /// 状态目录（告警状态）
+  #[serde(rename = "stateDir")]
+  pub state_dir: PathBuf,
+  /// 目录来源
+  #[serde(rename = "source")]
+  pub source: PathSource,
+}
+
+impl AppPaths {
+  /// 所有文件放在同一个目录下
+  fn single(dir: PathBuf, source: PathSource) -> Self {
+    Self {
+      config_dir: dir.clone(),
+      data_dir: dir.clone(),
+      state_dir: dir,
+      source,
+    }
+  }
+
+  /// 配置文件路径
+  pub fn config_file(&self) -> PathBuf {
+    self.config_dir.join("config.json")
+  }
+
+  /// 配置文件的 JSON Schema 路径
+  pub fn schema_file(&self) -> PathBuf {
+    self.config_dir.join("config.schema.json")
+  }
+
+  /// 配置备份目录
+  pub fn backup_dir(&self) -> PathBuf {
+    self.data_dir.join("backups")
+  }
+
+  /// 配置方案目录
+  pub fn profile_dir(&self) -> PathBuf {
+    self.config_dir.join("profiles")
+  }
+
+  /// 告警状态文件路径
+  pub fn alert_state_file(&self) -> PathBuf {
+    self.state_dir.join("alert_state.json")
+  }
+
+  /// 创建所有目录
+  fn create_dirs(&self) -> Result<(), String> {
+    for dir in [&self.config_dir, &self.data_dir, &self.state_dir] {
+      std::fs::create_dir_all(dir).map_err(|e| format!("无法创建目录 {:?}: {}", dir, e))?;
+    }
+    Ok(())
+  }
+}
+
+/// 从命令行参数中读取 --config-dir，支持 `--config-dir <目录>` 与 `--config-dir=<目录>`
+fn config_dir_arg(args: impl IntoIterator<Item = OsString>) -> Option<PathBuf> {
+  let mut args = args.into_iter();
+  while let Some(arg) = args.next() {
+    let arg = arg.to_string_lossy().into_owned();
+    if arg == CONFIG_DIR_ARG {
+      return args.next().map(PathBuf::from);
+    }
+    if let Some(dir) = arg.strip_prefix(CONFIG_DIR_ARG).and_then(|rest| rest.strip_prefix('=')) {
+      return Some(PathBuf::from(dir));
+    }
+  }
+  None
+}
+
+/// 读取非空的环境变量
+fn env_path(name: &str) -> Option<PathBuf> {
+  std::env::var_os(name)
+    .filter(|value| !value.is_empty())
+    .map(PathBuf::from)
+}
+
+/// 用户主目录
+fn home_dir() -> Result<PathBuf, String> {
+  env_path("HOME")
+    .or_else(|| env_path("USERPROFILE"))
+    .ok_or_else(|| "无法获取用户主目录（HOME / USERPROFILE 未设置）".to_string())
+}
+
+/// 便携模式目录，未启用便携模式时返回 Ok(None)
+fn portable_dir() -> Result<Option<PathBuf>, String> {
+  let exe = std::env::current_exe().map_err(|e| format!("无法获取可执行文件路径: {}", e))?;
+  let exe_dir = exe
+    .parent()
+    .ok_or_else(|| format!("无法获取可执行文件所在目录: {:?}", exe))?;
+
+  if env_path(PORTABLE_ENV).is_some() || exe_dir.join(PORTABLE_MARKER).exists() {
+    Ok(Some(exe_dir.join(PORTABLE_DIR_NAME)))
+  } else {
+    Ok(None)
+  }
+}
+
+/// XDG 目录：环境变量（`value`）未设置或不是绝对路径时使用规范中的默认值
+fn xdg_dir(value: Option<PathBuf>, home: &Path, default: &str) -> PathBuf {
+  value
+    .filter(|dir| dir.is_absolute())
+    .unwrap_or_else(|| home.join(default))
+    .join(APP_DIR_NAME)
+}
+
+/// 把旧目录中的文件移动到新位置
+/// 新位置已有配置文件时不迁移；单个文件迁移失败只记录日志
+fn migrate_legacy_dir(legacy: &Path, paths: &AppPaths) {
+  if !legacy.join("config.json").exists() || paths.config_file().exists() {
+    return;
+  }
+  log::info!("从旧目录 {:?} 迁移到 XDG 目录 {:?}", legacy, paths);
+
+  let mut moves = vec![
+    (legacy.join("config.json"), paths.config_file()),
+    (legacy.join("backups"), paths.backup_dir()),
+    (legacy.join("alert_state.json"), paths.alert_state_file()),
+  ];
+  // 版本迁移时留下的备份（config.v0.bak.json 等）
+  if let Ok(entries) = std::fs::read_dir(legacy) {
+    for entry in entries.filter_map(Result::ok) {
+      let name = entry.file_name().to_string_lossy().into_owned();
+      if name.starts_with("config.v") && name.ends_with(".bak.json") {
+        moves.push((entry.path(), paths.config_dir.join(&name)));
+      }
+    }
+  }
+
+  for (from, to) in moves {
+    if !from.exists() || to.exists() {
+      continue;
+    }
+    match std::fs::rename(&from, &to) {
+      Ok(_) => log::info!("已迁移 {:?} -> {:?}", from, to),
+      Err(e) => log::warn!("迁移 {:?} 失败: {}", from, e),
+    }
+  }
+}
+
+/// 确定应用目录并创建
+fn resolve() -> Result<AppPaths, String> {
+  let paths = if let Some(dir) = config_dir_arg(std::env::args_os().skip(1)) {
+    AppPaths::single(dir, PathSource::CommandLine)
+  } else if let Some(dir) = env_path(HOME_ENV) {
+    AppPaths::single(dir, PathSource::Environment)
+  } else if let Some(dir) = portable_dir()? {
+    AppPaths::single(dir, PathSource::Portable)
+  } else if cfg!(target_os = "linux") {
+    let home = home_dir()?;
+    let paths = AppPaths {
+      config_dir: xdg_dir(env_path("XDG_CONFIG_HOME"), &home, ".config"),
+      data_dir: xdg_dir(env_path("XDG_DATA_HOME"), &home, ".local/share"),
+      state_dir: xdg_dir(env_path("XDG_STATE_HOME"), &home, ".local/state"),
+      source: PathSource::Xdg,
+    };
+    paths.create_dirs()?;
+    migrate_legacy_dir(&home.join(LEGACY_DIR_NAME), &paths);
+    paths
+  } else {
+    AppPaths::single(home_dir()?.join(LEGACY_DIR_NAME), PathSource::Home)
+  };
+
+  paths.create_dirs()?;
+  log::info!("应用目录: {:?}", paths);
+  Ok(paths)
+}
+
+/// 获取应用目录
+/// 首次调用时确定并创建目录（Linux 上同时迁移旧目录），之后返回缓存的结果
+pub fn app_paths() -> Result<&'static AppPaths, String> {
+  static PATHS: OnceLock<Result<AppPaths, String>> = OnceLock::new();
+  PATHS.get_or_init(resolve).as_ref().map_err(Clone::clone)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::TempDir;
+  use std::fs;
+
+  fn args(list: &[&str]) -> Vec<OsString> {
+    list.iter().map(OsString::from).collect()
+  }
+
+  /// 按 XDG 布局在临时目录下创建新位置
+  fn xdg_paths(root: &Path) -> AppPaths {
+    let paths = AppPaths {
+      config_dir: root.join("config"),
+      data_dir: root.join("data"),
+      state_dir: root.join("state"),
+      source: PathSource::Xdg,
+    };
+    paths.create_dirs().unwrap();
+    paths
+  }
+
+  #[test]
+  fn config_dir_arg_forms() {
+    assert_eq!(config_dir_arg(args(&["--config-dir", "/a"])), Some(PathBuf::from("/a")));
+    assert_eq!(config_dir_arg(args(&["--verbose", "--config-dir=/b"])), Some(PathBuf::from("/b")));
+    assert_eq!(config_dir_arg(args(&["--config-dir"])), None);
+    assert_eq!(config_dir_arg(args(&["--config-directory=/c"])), None);
+    assert_eq!(config_dir_arg(args(&[])), None);
+  }
+
+  #[test]
+  fn xdg_dir_requires_absolute_paths() {
+    let home = Path::new("/home/user");
+    assert_eq!(
+      xdg_dir(Some(PathBuf::from("/xdg")), home, ".config"),
+      PathBuf::from("/xdg").join(APP_DIR_NAME)
+    );
+    assert_eq!(
+      xdg_dir(Some(PathBuf::from("relative")), home, ".config"),
+      home.join(".config").join(APP_DIR_NAME)
+    );
+    assert_eq!(xdg_dir(None, home, ".local/state"), home.join(".local/state").join(APP_DIR_NAME));
+  }
+
+  #[test]
+  fn single_dir_holds_every_file() {
+    let paths = AppPaths::single(PathBuf::from("/portable"), PathSource::Portable);
+    assert_eq!(paths.config_file(), Path::new("/portable/config.json"));
+    assert_eq!(paths.schema_file(), Path::new("/portable/config.schema.json"));
+    assert_eq!(paths.backup_dir(), Path::new("/portable/backups"));
+    assert_eq!(paths.profile_dir(), Path::new("/portable/profiles"));
+    assert_eq!(paths.alert_state_file(), Path::new("/portable/alert_state.json"));
+  }
+
+  #[test]
+  fn legacy_files_move_to_xdg_dirs() {
+    let root = TempDir::new();
+    let legacy = root.path().join("legacy");
+    fs::create_dir_all(legacy.join("backups")).unwrap();
+    fs::write(legacy.join("config.json"), "config").unwrap();
+    fs::write(legacy.join("alert_state.json"), "state").unwrap();
+    fs::write(legacy.join("backups/config-1.json"), "backup").unwrap();
+    fs::write(legacy.join("config.v0.bak.json"), "v0").unwrap();
+    fs::write(legacy.join("notes.txt"), "notes").unwrap();
+
+    let paths = xdg_paths(root.path());
+    migrate_legacy_dir(&legacy, &paths);
+
+    assert_eq!(fs::read_to_string(paths.config_file()).unwrap(), "config");
+    assert_eq!(fs::read_to_string(paths.alert_state_file()).unwrap(), "state");
+    assert_eq!(fs::read_to_string(paths.backup_dir().join("config-1.json")).unwrap(), "backup");
+    assert_eq!(fs::read_to_string(paths.config_dir.join("config.v0.bak.json")).unwrap(), "v0");
+    assert!(!legacy.join("config.json").exists());
+    assert!(legacy.join("notes.txt").exists());
+  }
+
+  #[test]
+  fn existing_xdg_config_is_not_replaced() {
+    let root = TempDir::new();
+    let legacy = root.path().join("legacy");
+    fs::create_dir_all(&legacy).unwrap();
+    fs::write(legacy.join("config.json"), "legacy").unwrap();
+    fs::write(legacy.join("alert_state.json"), "legacy state").unwrap();
+
+    let paths = xdg_paths(root.path());
+    fs::write(paths.config_file(), "current").unwrap();
+    migrate_legacy_dir(&legacy, &paths);
+
+    assert_eq!(fs::read_to_string(paths.config_file()).unwrap(), "current");
+    assert!(!paths.alert_state_file().exists());
+    assert!(legacy.join("config.json").exists());
+  }
+
+  #[test]
+  fn nothing_to_migrate_without_legacy_config() {
+    let root = TempDir::new();
+    let legacy = root.path().join("legacy");
+    fs::create_dir_all(&legacy).unwrap();
+    fs::write(legacy.join("alert_state.json"), "state").unwrap();
+
+    let paths = xdg_paths(root.path());
+    migrate_legacy_dir(&legacy, &paths);
+    assert!(!paths.alert_state_file().exists());
+  }
+}
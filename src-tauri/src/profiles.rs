@@ -0,0 +1,216 @@
+//! 命名配置方案
+//! 每个方案是配置目录下 profiles/<名称>.json 中保存的一份配置；
+//! config.json 始终是当前生效的配置，其中的 activeProfile 记录来自哪个方案。
+//! 方案不保存 Webhook 等密钥（bundle::SECRET_FIELDS），切换方案时沿用本机当前的密钥
+
+use crate::bundle::{LOCAL_FIELDS, SECRET_FIELDS};
+use crate::AppConfig;
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// 方案名称的最大长度（字符）
+const MAX_NAME_CHARS: usize = 64;
+/// 方案文件后缀
+const PROFILE_SUFFIX: &str = ".json";
+
+/// 方案信息
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ProfileInfo {
+  /// 方案名称
+  #[serde(rename = "name")]
+  pub name: String,
+  /// 是否为当前生效的方案
+  #[serde(rename = "active")]
+  pub active: bool,
+}
+
+/// 校验方案名称
+/// 只允许字母、数字（含中文）、空格、- 和 _，避免通过名称访问方案目录以外的文件
+pub fn validate_name(name: &str) -> Result<(), String> {
+  if name.trim().is_empty() {
+    return Err("方案名称不能为空".to_string());
+  }
+  if name.chars().count() > MAX_NAME_CHARS {
+    return Err(format!("方案名称不能超过 {} 个字符", MAX_NAME_CHARS));
+  }
+  if !name
+    .chars()
+    .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == ' ')
+  {
+    return Err("方案名称只能包含字母、数字、空格、- 和 _".to_string());
+  }
+  Ok(())
+}
+
+/// 方案文件路径
+pub fn profile_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+  validate_name(name)?;
+  Ok(dir.join(format!("{}{}", name, PROFILE_SUFFIX)))
+}
+
+/// 列出所有方案名称（按名称排序）；方案目录不存在时返回空列表
+pub fn list(dir: &Path) -> io::Result<Vec<String>> {
+  let entries = match fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(e),
+  };
+
+  let mut names: Vec<String> = entries
+    .filter_map(Result::ok)
+    .filter_map(|entry| {
+      let file_name = entry.file_name().into_string().ok()?;
+      let name = file_name.strip_suffix(PROFILE_SUFFIX)?;
+      validate_name(name).ok()?;
+      Some(name.to_string())
+    })
+    .collect();
+  names.sort();
+  Ok(names)
+}
+
+/// 读取方案内容
+pub fn read(dir: &Path, name: &str) -> Result<String, String> {
+  let path = profile_path(dir, name)?;
+  fs::read_to_string(&path).map_err(|e| match e.kind() {
+    io::ErrorKind::NotFound => format!("方案不存在: {}", name),
+    _ => format!("读取方案 {} 失败: {}", name, e),
+  })
+}
+
+/// 写入方案内容（原子写入）
+pub fn write(dir: &Path, name: &str, content: &str) -> Result<(), String> {
+  let path = profile_path(dir, name)?;
+  fs::create_dir_all(dir).map_err(|e| format!("无法创建方案目录 {:?}: {}", dir, e))?;
+  crate::storage::write_atomic(&path, content.as_bytes()).map_err(|e| format!("写入方案 {} 失败: {}", name, e))
+}
+
+/// 创建方案，同名方案已存在时返回错误
+/// 以 create_new 打开文件，存在检查与创建是同一个原子操作，并发创建同名方案时只有一个成功
+pub fn create(dir: &Path, name: &str, content: &str) -> Result<(), String> {
+  let path = profile_path(dir, name)?;
+  fs::create_dir_all(dir).map_err(|e| format!("无法创建方案目录 {:?}: {}", dir, e))?;
+  let mut file = OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .open(&path)
+    .map_err(|e| match e.kind() {
+      io::ErrorKind::AlreadyExists => format!("方案已存在: {}", name),
+      _ => format!("创建方案 {} 失败: {}", name, e),
+    })?;
+  if let Err(e) = file.write_all(content.as_bytes()).and_then(|_| file.sync_all()) {
+    drop(file);
+    let _ = fs::remove_file(&path);
+    return Err(format!("写入方案 {} 失败: {}", name, e));
+  }
+  Ok(())
+}
+
+/// 生成方案文件内容：去掉密钥与只对本机有意义的字段
+pub fn to_content(config: &AppConfig) -> Result<String, String> {
+  let mut value = serde_json::to_value(config).map_err(|e| format!("配置序列化失败: {}", e))?;
+  if let Value::Object(settings) = &mut value {
+    for field in SECRET_FIELDS.iter().chain(LOCAL_FIELDS) {
+      settings.remove(*field);
+    }
+  }
+  serde_json::to_string_pretty(&value).map_err(|e| format!("配置序列化失败: {}", e))
+}
+
+/// 从方案文件内容生成要切换到的配置
+/// 按版本迁移后，密钥字段沿用 `current` 中的取值（旧版方案文件中保存的密钥被忽略），activeProfile 设为 `name`
+pub fn load(content: &str, name: &str, current: &AppConfig) -> Result<AppConfig, String> {
+  let mut value: Value = serde_json::from_str(content).map_err(|e| format!("方案 {} 解析失败: {}", name, e))?;
+  crate::migration::migrate_config(&mut value)?;
+
+  let current = serde_json::to_value(current).map_err(|e| format!("配置序列化失败: {}", e))?;
+  if let Value::Object(settings) = &mut value {
+    for field in SECRET_FIELDS {
+      match current.get(*field) {
+        Some(secret) => settings.insert(field.to_string(), secret.clone()),
+        None => settings.remove(*field),
+      };
+    }
+    settings.insert("activeProfile".to_string(), Value::from(name));
+  }
+  serde_json::from_value(value).map_err(|e| format!("方案 {} 内容无效: {}", name, e))
+}
+
+/// 删除方案
+pub fn delete(dir: &Path, name: &str) -> Result<(), String> {
+  let path = profile_path(dir, name)?;
+  fs::remove_file(&path).map_err(|e| match e.kind() {
+    io::ErrorKind::NotFound => format!("方案不存在: {}", name),
+    _ => format!("删除方案 {} 失败: {}", name, e),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::{local_config, TempDir, WEBHOOK};
+
+  #[test]
+  fn names_are_validated() {
+    assert!(validate_name("sprint").is_ok());
+    assert!(validate_name("夜间 批处理_2").is_ok());
+    assert!(validate_name("").is_err());
+    assert!(validate_name("   ").is_err());
+    assert!(validate_name("../config").is_err());
+    assert!(validate_name("a/b").is_err());
+    assert!(validate_name(&"x".repeat(MAX_NAME_CHARS + 1)).is_err());
+  }
+
+  #[test]
+  fn content_excludes_secrets_and_active_profile() {
+    let content = to_content(&local_config()).unwrap();
+    assert!(!content.contains("wechatWorkWebhookUrl"));
+    assert!(!content.contains("activeProfile"));
+    assert!(content.contains("checkInterval"));
+  }
+
+  #[test]
+  fn load_carries_current_secrets_over() {
+    let defaults = to_content(&AppConfig::default()).unwrap();
+    let config = load(&defaults, "vacation", &local_config()).unwrap();
+    assert_eq!(config.wechat_work_webhook_url, WEBHOOK);
+    assert_eq!(config.active_profile.as_deref(), Some("vacation"));
+    assert_eq!(config.check_interval, AppConfig::default().check_interval);
+  }
+
+  #[test]
+  fn load_ignores_secrets_saved_by_older_versions() {
+    let legacy = serde_json::to_string(&AppConfig {
+      wechat_work_webhook_url: "https://example.com/stale".to_string(),
+      ..AppConfig::default()
+    })
+    .unwrap();
+    let config = load(&legacy, "old", &local_config()).unwrap();
+    assert_eq!(config.wechat_work_webhook_url, WEBHOOK);
+  }
+
+  #[test]
+  fn create_refuses_existing_profile() {
+    let dir = TempDir::new();
+    create(dir.path(), "sprint", "{}").unwrap();
+    assert_eq!(create(dir.path(), "sprint", "{\"x\":1}"), Err("方案已存在: sprint".to_string()));
+    assert_eq!(read(dir.path(), "sprint").unwrap(), "{}");
+    assert_eq!(list(dir.path()).unwrap(), vec!["sprint".to_string()]);
+  }
+
+  #[test]
+  fn write_list_and_delete() {
+    let dir = TempDir::new();
+    assert!(list(dir.path()).unwrap().is_empty());
+    write(dir.path(), "b", "{}").unwrap();
+    write(dir.path(), "a", "{}").unwrap();
+    fs::write(dir.path().join("notes.txt"), "x").unwrap();
+    assert_eq!(list(dir.path()).unwrap(), vec!["a".to_string(), "b".to_string()]);
+    delete(dir.path(), "a").unwrap();
+    assert!(delete(dir.path(), "a").is_err());
+    assert!(read(dir.path(), "a").is_err());
+    assert_eq!(list(dir.path()).unwrap(), vec!["b".to_string()]);
+  }
+}
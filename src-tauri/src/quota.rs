@@ -0,0 +1,286 @@
+//! 配额周期相关逻辑
+//! 解析页面上报的剩余重置时间，根据相邻两次读数判断配额是否已重置，
+//! 并计算重置前提醒
+
+use std::time::Duration;
+
+/// 使用量下降超过该值（百分点）视为可能的配额重置
+const QUOTA_RESET_MIN_DROP: f64 = 30.0;
+/// 重置后使用量应不高于该值（百分比）
+const QUOTA_RESET_MAX_PERCENT: f64 = 10.0;
+
+/// 解析剩余重置时间文本
+/// 支持注入脚本上报的格式，如 "1 小时 26 分钟后"、"30 分钟后"、"2 天 3 小时"
+/// 无法识别任何时间单位，或数值过大无法表示为时长时返回 None
+pub fn parse_reset_duration(text: &str) -> Option<Duration> {
+  // 按长度降序排列，保证 "分钟" 优先于 "分"、"mins" 优先于 "min"
+  const UNITS: &[(&str, u64)] = &[
+    ("小时", 3600),
+    ("钟头", 3600),
+    ("分钟", 60),
+    ("mins", 60),
+    ("hrs", 3600),
+    ("min", 60),
+    ("hr", 3600),
+    ("天", 86400),
+    ("日", 86400),
+    ("分", 60),
+    ("h", 3600),
+    ("d", 86400),
+  ];
+
+  let mut total_secs = 0.0;
+  let mut matched = false;
+  let mut rest = text;
+
+  while !rest.is_empty() {
+    // 跳到下一个数字
+    let Some(start) = rest.find(|c: char| c.is_ascii_digit()) else {
+      break;
+    };
+    rest = &rest[start..];
+
+    let end = rest
+      .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+      .unwrap_or(rest.len());
+    let number: f64 = match rest[..end].parse() {
+      Ok(n) => n,
+      Err(_) => {
+        rest = &rest[end..];
+        continue;
+      }
+    };
+    rest = rest[end..].trim_start();
+
+    let unit_match = UNITS.iter().find(|(unit, _)| {
+      rest.get(..unit.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(unit))
+    });
+    if let Some((unit, secs)) = unit_match {
+      total_secs += number * *secs as f64;
+      matched = true;
+      rest = &rest[unit.len()..];
+    }
+  }
+
+  if matched {
+    // 页面上超长的数字会解析为无穷大，from_secs_f64 遇到无穷大或溢出会 panic
+    Duration::try_from_secs_f64(total_secs).ok()
+  } else {
+    None
+  }
+}
+
+/// 判断配额是否已重置
+/// - 使用量大幅下降并回到较低水平；或
+/// - 已知的重置时间落在两次读数之间，且使用量有所下降
+pub fn is_quota_reset(
+  previous_percent: f64,
+  previous_at: u64,
+  current_percent: f64,
+  now: u64,
+  reset_at: Option<u64>,
+) -> bool {
+  let dropped = previous_percent - current_percent;
+  if dropped >= QUOTA_RESET_MIN_DROP && current_percent <= QUOTA_RESET_MAX_PERCENT {
+    return true;
+  }
+
+  match reset_at {
+    Some(reset_at) => previous_at < reset_at && reset_at <= now && dropped > 0.0,
+    None => false,
+  }
+}
+
+/// 重置时间变化超过该值（毫秒）视为进入了新的配额周期
+const PERIOD_CHANGE_TOLERANCE_MILLIS: u64 = 10 * 60 * 1000;
+
+/// 重置前提醒
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reminder {
+  /// 即将重置
+  PreReset { remaining_millis: u64 },
+  /// 临近重置但使用量仍然较低
+  UnusedQuota { usage: f64, remaining_millis: u64 },
+}
+
+/// 重置前提醒状态
+/// 每个配额周期内每种提醒只发送一次
+#[derive(Debug, Default, Clone)]
+pub struct ReminderState {
+  /// 当前周期的重置时间（Unix 毫秒）
+  period_reset_at: Option<u64>,
+  /// 本周期是否已发送重置前提醒
+  reminded: bool,
+  /// 本周期是否已发送未用完提醒
+  nudged: bool,
+}
+
+impl ReminderState {
+  /// 评估需要发送的提醒
+  /// `remind_before` / `nudge_before` 为 0 表示禁用对应提醒；`nudge_below` 为未用完提醒的使用量上限
+  pub fn evaluate(
+    &mut self,
+    now: u64,
+    reset_at: Option<u64>,
+    usage: Option<f64>,
+    remind_before: Duration,
+    nudge_before: Duration,
+    nudge_below: f64,
+  ) -> Vec<Reminder> {
+    let Some(reset_at) = reset_at else {
+      return Vec::new();
+    };
+
+    // 重置时间明显变化，说明进入了新的周期
+    let new_period = self
+      .period_reset_at
+      .map_or(true, |current| current.abs_diff(reset_at) > PERIOD_CHANGE_TOLERANCE_MILLIS);
+    if new_period {
+      *self = Self {
+        period_reset_at: Some(reset_at),
+        ..Self::default()
+      };
+    }
+
+    if reset_at <= now {
+      return Vec::new();
+    }
+    let remaining_millis = reset_at - now;
+    let mut reminders = Vec::new();
+
+    if !remind_before.is_zero() && !self.reminded && remaining_millis <= remind_before.as_millis() as u64 {
+      self.reminded = true;
+      reminders.push(Reminder::PreReset { remaining_millis });
+    }
+
+    if let Some(usage) = usage {
+      if !nudge_before.is_zero()
+        && !self.nudged
+        && usage < nudge_below
+        && remaining_millis <= nudge_before.as_millis() as u64
+      {
+        self.nudged = true;
+        reminders.push(Reminder::UnusedQuota { usage, remaining_millis });
+      }
+    }
+
+    reminders
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn secs(text: &str) -> Option<u64> {
+    parse_reset_duration(text).map(|duration| duration.as_secs())
+  }
+
+  #[test]
+  fn parses_script_formats() {
+    assert_eq!(secs("1 小时 26 分钟后"), Some(3600 + 26 * 60));
+    assert_eq!(secs("30 分钟后"), Some(30 * 60));
+    assert_eq!(secs("5 小时后"), Some(5 * 3600));
+    assert_eq!(secs("2 天 3 小时后"), Some(2 * 86400 + 3 * 3600));
+    assert_eq!(secs("2 天 3 小时 10 分钟后"), Some(2 * 86400 + 3 * 3600 + 600));
+  }
+
+  #[test]
+  fn parses_compact_and_english_units() {
+    assert_eq!(secs("1小时26分钟后"), Some(3600 + 26 * 60));
+    assert_eq!(secs("2d 3h"), Some(2 * 86400 + 3 * 3600));
+    assert_eq!(secs("1 hr 5 mins"), Some(3600 + 300));
+    assert_eq!(secs("45 MIN"), Some(45 * 60));
+  }
+
+  #[test]
+  fn parses_fractional_numbers() {
+    assert_eq!(secs("1.5 小时"), Some(5400));
+  }
+
+  #[test]
+  fn rejects_text_without_units() {
+    assert_eq!(secs(""), None);
+    assert_eq!(secs("即将重置"), None);
+    assert_eq!(secs("42"), None);
+    assert_eq!(secs("1..2 小时"), None);
+  }
+
+  #[test]
+  fn rejects_durations_too_large_to_represent() {
+    let digits = "9".repeat(400);
+    assert_eq!(secs(&format!("{} 小时", digits)), None);
+    assert_eq!(secs("99999999999999999999 天"), None);
+  }
+
+  #[test]
+  fn large_drop_to_low_usage_is_a_reset() {
+    assert!(is_quota_reset(85.0, 0, 3.0, 1_000, None));
+    assert!(!is_quota_reset(85.0, 0, 60.0, 1_000, None));
+    assert!(!is_quota_reset(20.0, 0, 5.0, 1_000, None));
+  }
+
+  #[test]
+  fn passing_known_reset_time_with_any_drop_is_a_reset() {
+    assert!(is_quota_reset(50.0, 0, 45.0, 2_000, Some(1_000)));
+    // 重置时间尚未到达
+    assert!(!is_quota_reset(50.0, 0, 45.0, 2_000, Some(3_000)));
+    // 使用量没有下降
+    assert!(!is_quota_reset(50.0, 0, 50.0, 2_000, Some(1_000)));
+  }
+
+  const MINUTE: u64 = 60_000;
+
+  fn minutes(n: u64) -> Duration {
+    Duration::from_secs(n * 60)
+  }
+
+  #[test]
+  fn reminders_need_a_reset_time() {
+    let mut state = ReminderState::default();
+    assert!(state.evaluate(0, None, Some(10.0), minutes(30), minutes(30), 50.0).is_empty());
+  }
+
+  #[test]
+  fn pre_reset_reminder_fires_once_per_period() {
+    let mut state = ReminderState::default();
+    let reset_at = 100 * MINUTE;
+    assert!(state.evaluate(60 * MINUTE, Some(reset_at), None, minutes(30), Duration::ZERO, 50.0).is_empty());
+    assert_eq!(
+      state.evaluate(75 * MINUTE, Some(reset_at), None, minutes(30), Duration::ZERO, 50.0),
+      vec![Reminder::PreReset { remaining_millis: 25 * MINUTE }]
+    );
+    assert!(state.evaluate(80 * MINUTE, Some(reset_at), None, minutes(30), Duration::ZERO, 50.0).is_empty());
+  }
+
+  #[test]
+  fn unused_quota_nudge_requires_low_usage() {
+    let mut state = ReminderState::default();
+    let reset_at = 100 * MINUTE;
+    assert!(state.evaluate(80 * MINUTE, Some(reset_at), Some(60.0), Duration::ZERO, minutes(30), 50.0).is_empty());
+    assert_eq!(
+      state.evaluate(81 * MINUTE, Some(reset_at), Some(20.0), Duration::ZERO, minutes(30), 50.0),
+      vec![Reminder::UnusedQuota { usage: 20.0, remaining_millis: 19 * MINUTE }]
+    );
+    assert!(state.evaluate(82 * MINUTE, Some(reset_at), Some(20.0), Duration::ZERO, minutes(30), 50.0).is_empty());
+  }
+
+  #[test]
+  fn new_period_rearms_reminders() {
+    let mut state = ReminderState::default();
+    assert_eq!(state.evaluate(75 * MINUTE, Some(100 * MINUTE), None, minutes(30), Duration::ZERO, 50.0).len(), 1);
+    // 页面重新计算的剩余时间有少量误差，仍属于同一周期
+    assert!(state.evaluate(76 * MINUTE, Some(101 * MINUTE), None, minutes(30), Duration::ZERO, 50.0).is_empty());
+    // 重置后进入新的周期
+    let next_reset = 100 * MINUTE + 24 * 60 * MINUTE;
+    assert!(state.evaluate(101 * MINUTE, Some(next_reset), None, minutes(30), Duration::ZERO, 50.0).is_empty());
+    assert_eq!(state.evaluate(next_reset - 10 * MINUTE, Some(next_reset), None, minutes(30), Duration::ZERO, 50.0).len(), 1);
+  }
+
+  #[test]
+  fn passed_reset_time_gives_no_reminders() {
+    let mut state = ReminderState::default();
+    assert!(state.evaluate(100 * MINUTE, Some(100 * MINUTE), Some(5.0), minutes(30), minutes(30), 50.0).is_empty());
+  }
+}
+
@@ -0,0 +1,730 @@
+//! 自定义告警规则
+//! 提供一个小型表达式语言，规则条件可以引用当前读数、上一次读数、历史读数、重置时间与当前时刻，
+//! 例如 `usage > 80 && reset_in_minutes > 120`、`delta_within(10) >= 20`（10 分钟内上涨 20 个百分点）
+//!
+//! 支持的语法：
+//! - 数字、`true` / `false`、变量名
+//! - 函数 `delta_within(分钟)`：当前使用量减去最近若干分钟内的最低读数
+//! - 算术 `+ - * /`，比较 `> >= < <= == !=`
+//! - 逻辑 `&&` / `and`、`||` / `or`、`!` / `not`，以及括号
+//!
+//! 表达式最多嵌套 [`MAX_DEPTH`] 层（括号、函数参数、一元运算与连续的二元运算都计入），避免递归解析与求值时栈溢出。
+//! 可用变量见 [`RuleContext`]；引用了当前没有值的变量（如首次读数时的 `previous`）时规则不触发。
+//! 条件在配置生效时由 [`RuleState::set_rules`] 解析一次，之后每次读数只求值
+
+use crate::alerts::{NotificationChannel, Severity};
+use std::collections::HashSet;
+
+/// 规则触发时的默认通知内容
+/// 支持占位符 {name}、{usage}、{condition}
+pub const DEFAULT_RULE_MESSAGE: &str = "规则「{name}」已触发，当前使用量 {usage}%。";
+
+/// 变量名列表，用于校验与错误提示
+const VARIABLES: &[&str] = &[
+  "usage",
+  "previous",
+  "delta",
+  "elapsed_minutes",
+  "reset_in_minutes",
+  "hour",
+  "minute",
+];
+
+/// 函数名列表
+const FUNCTIONS: &[&str] = &["delta_within"];
+
+/// 表达式的最大嵌套层数
+pub const MAX_DEPTH: usize = 64;
+
+/// 自定义告警规则
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, Debug, PartialEq)]
+pub struct AlertRule {
+  /// 规则名称，同时作为去重的标识
+  #[serde(rename = "name")]
+  pub name: String,
+  /// 触发条件表达式
+  #[serde(rename = "condition")]
+  pub condition: String,
+  /// 告警级别
+  #[serde(rename = "severity", default = "default_rule_severity")]
+  pub severity: Severity,
+  /// 通知渠道
+  #[serde(rename = "channels", default = "crate::alerts::all_channels")]
+  #[schemars(length(min = 1))]
+  pub channels: Vec<NotificationChannel>,
+  /// 通知内容模板，为空时使用默认模板
+  #[serde(rename = "messageTemplate", default)]
+  pub message_template: Option<String>,
+  /// 是否启用
+  #[serde(rename = "enabled", default = "default_rule_enabled")]
+  pub enabled: bool,
+}
+
+fn default_rule_severity() -> Severity {
+  Severity::Warning
+}
+
+fn default_rule_enabled() -> bool {
+  true
+}
+
+impl AlertRule {
+  /// 渲染通知内容
+  pub fn render_message(&self, usage: f64) -> String {
+    self
+      .message_template
+      .as_deref()
+      .filter(|template| !template.trim().is_empty())
+      .unwrap_or(DEFAULT_RULE_MESSAGE)
+      .replace("{name}", &self.name)
+      .replace("{usage}", &format!("{:.1}", usage))
+      .replace("{condition}", &self.condition)
+  }
+}
+
+/// 规则求值上下文
+#[derive(Debug, Clone, Default)]
+pub struct RuleContext {
+  /// 当前使用量（百分比）
+  pub usage: f64,
+  /// 本配额周期内的读数 (Unix 毫秒, 百分比)，按时间升序，最后一个为当前读数
+  pub history: Vec<(u64, f64)>,
+  /// 上一次使用量（百分比）
+  pub previous: Option<f64>,
+  /// 距上一次读数的分钟数
+  pub elapsed_minutes: Option<f64>,
+  /// 距配额重置的分钟数
+  pub reset_in_minutes: Option<f64>,
+  /// 当前本地时间的小时（0-23）
+  pub hour: f64,
+  /// 当前本地时间的分钟（0-59）
+  pub minute: f64,
+}
+
+impl RuleContext {
+  /// 读取变量，变量存在但当前没有值时返回 Ok(None)
+  fn variable(&self, name: &str) -> Result<Option<f64>, String> {
+    Ok(match name {
+      "usage" => Some(self.usage),
+      "previous" => self.previous,
+      "delta" => self.previous.map(|previous| self.usage - previous),
+      "elapsed_minutes" => self.elapsed_minutes,
+      "reset_in_minutes" => self.reset_in_minutes,
+      "hour" => Some(self.hour),
+      "minute" => Some(self.minute),
+      _ => return Err(unknown_variable(name)),
+    })
+  }
+
+  /// 调用函数，当前没有足够的数据时返回 Ok(None)
+  fn call(&self, name: &str, argument: f64) -> Result<Option<f64>, String> {
+    match name {
+      "delta_within" => {
+        if !argument.is_finite() || argument <= 0.0 {
+          return Err(format!("delta_within 的参数必须是正数分钟数，实际为 {}", argument));
+        }
+        let window_millis = (argument * 60_000.0) as u64;
+        Ok(crate::forecast::rise_within(&self.history, window_millis).map(|rise| rise.delta))
+      }
+      _ => Err(unknown_function(name)),
+    }
+  }
+}
+
+fn unknown_variable(name: &str) -> String {
+  format!("未知变量 {}，可用变量: {}", name, VARIABLES.join(", "))
+}
+
+fn unknown_function(name: &str) -> String {
+  format!("未知函数 {}，可用函数: {}", name, FUNCTIONS.join(", "))
+}
+
+/// 词法单元
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Number(f64),
+  Ident(String),
+  Op(&'static str),
+  LParen,
+  RParen,
+}
+
+/// 词法分析
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+  // 双字符运算符放在前面，保证 ">=" 优先于 ">"
+  const OPERATORS: &[&str] = &[">=", "<=", "==", "!=", "&&", "||", ">", "<", "!", "+", "-", "*", "/"];
+
+  let mut tokens = Vec::new();
+  let mut rest = source;
+
+  while let Some(c) = rest.chars().next() {
+    if c.is_whitespace() {
+      rest = &rest[c.len_utf8()..];
+    } else if c.is_ascii_digit() || c == '.' {
+      let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(rest.len());
+      let number = rest[..end]
+        .parse()
+        .map_err(|_| format!("无效的数字 {}", &rest[..end]))?;
+      tokens.push(Token::Number(number));
+      rest = &rest[end..];
+    } else if c.is_ascii_alphabetic() || c == '_' {
+      let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+      tokens.push(Token::Ident(rest[..end].to_string()));
+      rest = &rest[end..];
+    } else if c == '(' {
+      tokens.push(Token::LParen);
+      rest = &rest[1..];
+    } else if c == ')' {
+      tokens.push(Token::RParen);
+      rest = &rest[1..];
+    } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+      tokens.push(Token::Op(op));
+      rest = &rest[op.len()..];
+    } else {
+      return Err(format!("无法识别的字符 '{}'", c));
+    }
+  }
+
+  Ok(tokens)
+}
+
+/// 语法树
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+  Number(f64),
+  Bool(bool),
+  Variable(String),
+  Call(&'static str, Box<Expr>),
+  Neg(Box<Expr>),
+  Not(Box<Expr>),
+  Binary(&'static str, Box<Expr>, Box<Expr>),
+}
+
+/// 递归下降语法分析
+/// 优先级从低到高：|| < && < ! < 比较 < + - < * / < 一元负号
+struct Parser {
+  tokens: Vec<Token>,
+  pos: usize,
+  /// 当前嵌套层数
+  depth: usize,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos)
+  }
+
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.pos).cloned();
+    self.pos += 1;
+    token
+  }
+
+  /// 匹配运算符，`and` / `or` / `not` 视为 `&&` / `||` / `!` 的别名
+  fn eat_op(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+    let op = match self.peek()? {
+      Token::Op(op) => *op,
+      Token::Ident(word) => match word.as_str() {
+        "and" => "&&",
+        "or" => "||",
+        "not" => "!",
+        _ => return None,
+      },
+      _ => return None,
+    };
+    if ops.contains(&op) {
+      self.pos += 1;
+      Some(op)
+    } else {
+      None
+    }
+  }
+
+  /// 进入下一层嵌套，超过 MAX_DEPTH 时返回错误
+  /// 调用方在这一层解析完后恢复进入前的 depth
+  fn enter(&mut self) -> Result<(), String> {
+    if self.depth >= MAX_DEPTH {
+      return Err(format!("表达式嵌套过深（超过 {} 层）", MAX_DEPTH));
+    }
+    self.depth += 1;
+    Ok(())
+  }
+
+  fn parse_or(&mut self) -> Result<Expr, String> {
+    let depth = self.depth;
+    let mut left = self.parse_and()?;
+    while let Some(op) = self.eat_op(&["||"]) {
+      self.enter()?;
+      left = Expr::Binary(op, Box::new(left), Box::new(self.parse_and()?));
+    }
+    self.depth = depth;
+    Ok(left)
+  }
+
+  fn parse_and(&mut self) -> Result<Expr, String> {
+    let depth = self.depth;
+    let mut left = self.parse_not()?;
+    while let Some(op) = self.eat_op(&["&&"]) {
+      self.enter()?;
+      left = Expr::Binary(op, Box::new(left), Box::new(self.parse_not()?));
+    }
+    self.depth = depth;
+    Ok(left)
+  }
+
+  fn parse_not(&mut self) -> Result<Expr, String> {
+    if self.eat_op(&["!"]).is_some() {
+      let depth = self.depth;
+      self.enter()?;
+      let operand = self.parse_not()?;
+      self.depth = depth;
+      return Ok(Expr::Not(Box::new(operand)));
+    }
+    self.parse_comparison()
+  }
+
+  fn parse_comparison(&mut self) -> Result<Expr, String> {
+    let left = self.parse_sum()?;
+    match self.eat_op(&[">", ">=", "<", "<=", "==", "!="]) {
+      Some(op) => Ok(Expr::Binary(op, Box::new(left), Box::new(self.parse_sum()?))),
+      None => Ok(left),
+    }
+  }
+
+  fn parse_sum(&mut self) -> Result<Expr, String> {
+    let depth = self.depth;
+    let mut left = self.parse_product()?;
+    while let Some(op) = self.eat_op(&["+", "-"]) {
+      self.enter()?;
+      left = Expr::Binary(op, Box::new(left), Box::new(self.parse_product()?));
+    }
+    self.depth = depth;
+    Ok(left)
+  }
+
+  fn parse_product(&mut self) -> Result<Expr, String> {
+    let depth = self.depth;
+    let mut left = self.parse_unary()?;
+    while let Some(op) = self.eat_op(&["*", "/"]) {
+      self.enter()?;
+      left = Expr::Binary(op, Box::new(left), Box::new(self.parse_unary()?));
+    }
+    self.depth = depth;
+    Ok(left)
+  }
+
+  fn parse_unary(&mut self) -> Result<Expr, String> {
+    if self.eat_op(&["-"]).is_some() {
+      let depth = self.depth;
+      self.enter()?;
+      let operand = self.parse_unary()?;
+      self.depth = depth;
+      return Ok(Expr::Neg(Box::new(operand)));
+    }
+    self.parse_primary()
+  }
+
+  fn parse_primary(&mut self) -> Result<Expr, String> {
+    match self.next() {
+      Some(Token::Number(n)) => Ok(Expr::Number(n)),
+      Some(Token::Ident(name)) if self.peek() == Some(&Token::LParen) => {
+        let function = FUNCTIONS
+          .iter()
+          .find(|function| **function == name)
+          .ok_or_else(|| unknown_function(&name))?;
+        self.pos += 1;
+        let depth = self.depth;
+        self.enter()?;
+        let argument = self.parse_or()?;
+        self.depth = depth;
+        match self.next() {
+          Some(Token::RParen) => Ok(Expr::Call(function, Box::new(argument))),
+          _ => Err(format!("函数 {} 缺少右括号", name)),
+        }
+      }
+      Some(Token::Ident(name)) => match name.as_str() {
+        "true" => Ok(Expr::Bool(true)),
+        "false" => Ok(Expr::Bool(false)),
+        _ if VARIABLES.contains(&name.as_str()) => Ok(Expr::Variable(name)),
+        _ if FUNCTIONS.contains(&name.as_str()) => Err(format!("函数 {} 需要参数，如 {}(10)", name, name)),
+        _ => Err(unknown_variable(&name)),
+      },
+      Some(Token::LParen) => {
+        let depth = self.depth;
+        self.enter()?;
+        let expr = self.parse_or()?;
+        self.depth = depth;
+        match self.next() {
+          Some(Token::RParen) => Ok(expr),
+          _ => Err("缺少右括号".to_string()),
+        }
+      }
+      Some(token) => Err(format!("意外的符号 {:?}", token)),
+      None => Err("表达式不完整".to_string()),
+    }
+  }
+}
+
+/// 求值结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+  Number(f64),
+  Bool(bool),
+}
+
+/// 已解析的规则条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+  expr: Expr,
+}
+
+impl Condition {
+  /// 解析条件表达式
+  pub fn parse(source: &str) -> Result<Self, String> {
+    let tokens = tokenize(source)?;
+    if tokens.is_empty() {
+      return Err("条件表达式为空".to_string());
+    }
+
+    let mut parser = Parser { tokens, pos: 0, depth: 0 };
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+      return Err(format!("意外的符号 {:?}", token));
+    }
+    Ok(Self { expr })
+  }
+
+  /// 对上下文求值
+  /// 引用的变量当前没有值时返回 Ok(false)；结果不是布尔值时返回错误
+  pub fn evaluate(&self, context: &RuleContext) -> Result<bool, String> {
+    match eval(&self.expr, context)? {
+      Some(Value::Bool(result)) => Ok(result),
+      Some(Value::Number(_)) => Err("条件表达式的结果必须是布尔值".to_string()),
+      None => Ok(false),
+    }
+  }
+}
+
+/// 递归求值，变量缺失时返回 Ok(None)
+fn eval(expr: &Expr, context: &RuleContext) -> Result<Option<Value>, String> {
+  let number = |value: Value| match value {
+    Value::Number(n) => Ok(n),
+    Value::Bool(_) => Err("算术或比较运算需要数字".to_string()),
+  };
+  let boolean = |value: Value| match value {
+    Value::Bool(b) => Ok(b),
+    Value::Number(_) => Err("逻辑运算需要布尔值".to_string()),
+  };
+
+  Ok(Some(match expr {
+    Expr::Number(n) => Value::Number(*n),
+    Expr::Bool(b) => Value::Bool(*b),
+    Expr::Variable(name) => match context.variable(name)? {
+      Some(value) => Value::Number(value),
+      None => return Ok(None),
+    },
+    Expr::Call(name, argument) => match eval(argument, context)? {
+      Some(argument) => match context.call(name, number(argument)?)? {
+        Some(value) => Value::Number(value),
+        None => return Ok(None),
+      },
+      None => return Ok(None),
+    },
+    Expr::Neg(inner) => match eval(inner, context)? {
+      Some(value) => Value::Number(-number(value)?),
+      None => return Ok(None),
+    },
+    Expr::Not(inner) => match eval(inner, context)? {
+      Some(value) => Value::Bool(!boolean(value)?),
+      None => return Ok(None),
+    },
+    Expr::Binary(op @ ("&&" | "||"), left, right) => {
+      // 短路求值；缺失的一侧视为 false
+      let left = eval(left, context)?.map(boolean).transpose()?.unwrap_or(false);
+      if (*op == "&&" && !left) || (*op == "||" && left) {
+        return Ok(Some(Value::Bool(left)));
+      }
+      Value::Bool(eval(right, context)?.map(boolean).transpose()?.unwrap_or(false))
+    }
+    Expr::Binary(op, left, right) => {
+      let (Some(left), Some(right)) = (eval(left, context)?, eval(right, context)?) else {
+        return Ok(None);
+      };
+      let (left, right) = (number(left)?, number(right)?);
+      match *op {
+        "+" => Value::Number(left + right),
+        "-" => Value::Number(left - right),
+        "*" => Value::Number(left * right),
+        "/" => Value::Number(left / right),
+        ">" => Value::Bool(left > right),
+        ">=" => Value::Bool(left >= right),
+        "<" => Value::Bool(left < right),
+        "<=" => Value::Bool(left <= right),
+        "==" => Value::Bool(left == right),
+        "!=" => Value::Bool(left != right),
+        _ => return Err(format!("不支持的运算符 {}", op)),
+      }
+    }
+  }))
+}
+
+/// 规则触发状态
+/// 规则只在条件由假变真时触发一次，条件恢复为假后重新布防
+#[derive(Debug, Default, Clone)]
+pub struct RuleState {
+  /// 启用的规则及解析后的条件（解析失败时保存错误）
+  compiled: Vec<(AlertRule, Result<Condition, String>)>,
+  /// 当前条件成立的规则名称
+  active: HashSet<String>,
+}
+
+impl RuleState {
+  /// 设置规则并解析条件，配置生效时调用
+  /// 无法解析的规则记录日志，求值时跳过
+  pub fn set_rules(&mut self, rules: &[AlertRule]) {
+    self.compiled = rules
+      .iter()
+      .filter(|rule| rule.enabled)
+      .map(|rule| {
+        let condition = Condition::parse(&rule.condition);
+        if let Err(e) = &condition {
+          log::warn!("[rules] 规则「{}」的条件无法解析，已跳过: {}", rule.name, e);
+        }
+        (rule.clone(), condition)
+      })
+      .collect();
+
+    // 清理已删除或停用的规则
+    let compiled = &self.compiled;
+    self.active.retain(|name| compiled.iter().any(|(rule, _)| &rule.name == name));
+  }
+
+  /// 是否有启用的规则
+  pub fn is_empty(&self) -> bool {
+    self.compiled.is_empty()
+  }
+
+  /// 评估所有启用的规则，返回本次新触发的规则
+  /// 求值出错的规则会被跳过并记录日志
+  pub fn evaluate(&mut self, context: &RuleContext) -> Vec<AlertRule> {
+    let mut fired = Vec::new();
+
+    for (rule, condition) in &self.compiled {
+      let Ok(condition) = condition else {
+        continue;
+      };
+      match condition.evaluate(context) {
+        Ok(true) => {
+          if self.active.insert(rule.name.clone()) {
+            fired.push(rule.clone());
+          }
+        }
+        Ok(false) => {
+          self.active.remove(&rule.name);
+        }
+        Err(e) => log::warn!("[rules] 规则「{}」求值失败: {}", rule.name, e),
+      }
+    }
+
+    fired
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn context(usage: f64) -> RuleContext {
+    RuleContext {
+      usage,
+      history: vec![(0, usage)],
+      hour: 12.0,
+      ..RuleContext::default()
+    }
+  }
+
+  fn eval_with(source: &str, context: &RuleContext) -> Result<bool, String> {
+    Condition::parse(source)?.evaluate(context)
+  }
+
+  fn eval_str(source: &str) -> Result<bool, String> {
+    eval_with(source, &context(50.0))
+  }
+
+  fn rule(name: &str, condition: &str) -> AlertRule {
+    AlertRule {
+      name: name.to_string(),
+      condition: condition.to_string(),
+      severity: Severity::Warning,
+      channels: crate::alerts::all_channels(),
+      message_template: None,
+      enabled: true,
+    }
+  }
+
+  #[test]
+  fn arithmetic_precedence_and_associativity() {
+    assert_eq!(eval_str("1 + 2 * 3 == 7"), Ok(true));
+    assert_eq!(eval_str("(1 + 2) * 3 == 9"), Ok(true));
+    assert_eq!(eval_str("10 - 2 - 3 == 5"), Ok(true));
+    assert_eq!(eval_str("8 / 2 / 2 == 2"), Ok(true));
+    assert_eq!(eval_str("-2 * 3 == -6"), Ok(true));
+    assert_eq!(eval_str("--1 == 1"), Ok(true));
+  }
+
+  #[test]
+  fn logical_precedence() {
+    // && 优先于 ||
+    assert_eq!(eval_str("true || false && false"), Ok(true));
+    assert_eq!(eval_str("(true || false) && false"), Ok(false));
+    // ! 优先于 &&，低于比较
+    assert_eq!(eval_str("!false && false"), Ok(false));
+    assert_eq!(eval_str("!usage > 80"), Ok(true));
+    assert_eq!(eval_str("not usage > 40 or usage == 50"), Ok(true));
+    assert_eq!(eval_str("usage > 40 and not false"), Ok(true));
+  }
+
+  #[test]
+  fn comparisons_do_not_chain() {
+    assert!(Condition::parse("1 < 2 < 3").is_err());
+  }
+
+  #[test]
+  fn parenthesis_errors() {
+    assert_eq!(Condition::parse("(usage > 1").unwrap_err(), "缺少右括号");
+    assert!(Condition::parse("usage > 1)").unwrap_err().contains("意外的符号"));
+    assert!(Condition::parse("()").is_err());
+    assert!(Condition::parse("delta_within(10 > 1").unwrap_err().contains("缺少右括号"));
+  }
+
+  #[test]
+  fn nesting_depth_is_limited() {
+    let nested = |depth: usize| format!("{}usage > 1{}", "(".repeat(depth), ")".repeat(depth));
+    assert!(Condition::parse(&nested(MAX_DEPTH)).is_ok());
+    assert!(Condition::parse(&nested(MAX_DEPTH + 1)).unwrap_err().contains("嵌套过深"));
+    // 足以让不限层数的递归栈溢出
+    assert!(Condition::parse(&nested(100_000)).unwrap_err().contains("嵌套过深"));
+    assert!(Condition::parse(&format!("{}true", "!".repeat(100_000))).unwrap_err().contains("嵌套过深"));
+    assert!(Condition::parse(&format!("{}1 > 0", "-".repeat(100_000))).unwrap_err().contains("嵌套过深"));
+    assert!(Condition::parse(&format!("usage{} > 0", " + 1".repeat(100_000))).unwrap_err().contains("嵌套过深"));
+    assert!(Condition::parse(&format!("delta_within({}10{}) > 0", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH))).is_err());
+  }
+
+  #[test]
+  fn unknown_identifiers_and_functions() {
+    assert!(Condition::parse("usgae > 80").unwrap_err().contains("未知变量 usgae"));
+    assert!(Condition::parse("max(1) > 0").unwrap_err().contains("未知函数 max"));
+    assert!(Condition::parse("delta_within > 1").unwrap_err().contains("需要参数"));
+    assert!(Condition::parse("usage > 80 and").unwrap_err().contains("不完整"));
+    assert!(Condition::parse("usage $ 80").unwrap_err().contains("无法识别"));
+  }
+
+  #[test]
+  fn empty_condition_is_rejected() {
+    assert!(Condition::parse("").is_err());
+    assert!(Condition::parse("   ").is_err());
+  }
+
+  #[test]
+  fn numeric_edge_cases() {
+    assert_eq!(eval_str(".5 < 1"), Ok(true));
+    assert_eq!(eval_str("usage == 50.0"), Ok(true));
+    assert!(Condition::parse("1.2.3 > 0").unwrap_err().contains("无效的数字"));
+    // 不支持科学计数法
+    assert!(Condition::parse("usage < 1e3").is_err());
+    // 除以 0 得到无穷大，0 / 0 得到 NaN，与任何数比较都不成立
+    assert_eq!(eval_str("usage / 0 > 1000000"), Ok(true));
+    assert_eq!(eval_str("0 / 0 == 0 / 0"), Ok(false));
+  }
+
+  #[test]
+  fn type_errors() {
+    assert!(eval_str("usage + 1").is_err());
+    assert!(eval_str("true > 1").is_err());
+    assert!(eval_str("usage && true").is_err());
+  }
+
+  #[test]
+  fn missing_variables_do_not_fire() {
+    assert_eq!(eval_str("previous > 10"), Ok(false));
+    assert_eq!(eval_str("!(previous > 10)"), Ok(false));
+    assert_eq!(eval_str("previous > 10 || usage > 40"), Ok(true));
+    assert_eq!(eval_str("reset_in_minutes < 30"), Ok(false));
+  }
+
+  #[test]
+  fn delta_and_elapsed_use_previous_reading() {
+    let context = RuleContext {
+      previous: Some(30.0),
+      elapsed_minutes: Some(5.0),
+      ..context(50.0)
+    };
+    assert_eq!(eval_with("delta >= 20 && elapsed_minutes <= 10", &context), Ok(true));
+  }
+
+  #[test]
+  fn delta_within_looks_back_over_history() {
+    const MINUTE: u64 = 60_000;
+    let context = RuleContext {
+      usage: 55.0,
+      history: vec![(0, 20.0), (20 * MINUTE, 30.0), (25 * MINUTE, 40.0), (30 * MINUTE, 55.0)],
+      ..RuleContext::default()
+    };
+    assert_eq!(eval_with("delta_within(10) >= 20", &context), Ok(true));
+    assert_eq!(eval_with("delta_within(10) >= 30", &context), Ok(false));
+    assert_eq!(eval_with("delta_within(30) >= 35", &context), Ok(true));
+    assert_eq!(eval_with("delta_within(2 * 5) == 25", &context), Ok(true));
+    // 窗口内没有较早的读数
+    assert_eq!(eval_with("delta_within(1) >= 0", &context), Ok(false));
+    assert!(eval_with("delta_within(0) > 1", &context).is_err());
+    assert!(eval_with("delta_within(-5) > 1", &context).is_err());
+    assert!(eval_with("delta_within(true) > 1", &context).is_err());
+  }
+
+  #[test]
+  fn rule_fires_once_until_condition_clears() {
+    let mut state = RuleState::default();
+    state.set_rules(&[rule("high", "usage > 80")]);
+
+    assert!(state.evaluate(&context(50.0)).is_empty());
+    assert_eq!(state.evaluate(&context(85.0)).len(), 1);
+    assert!(state.evaluate(&context(90.0)).is_empty());
+    assert!(state.evaluate(&context(70.0)).is_empty());
+    assert_eq!(state.evaluate(&context(85.0)).len(), 1);
+  }
+
+  #[test]
+  fn disabled_and_invalid_rules_are_skipped() {
+    let mut disabled = rule("disabled", "true");
+    disabled.enabled = false;
+    let mut state = RuleState::default();
+    state.set_rules(&[disabled, rule("broken", "usage >"), rule("ok", "true")]);
+
+    let fired = state.evaluate(&context(50.0));
+    assert_eq!(fired.iter().map(|rule| rule.name.as_str()).collect::<Vec<_>>(), vec!["ok"]);
+  }
+
+  #[test]
+  fn replacing_rules_forgets_removed_ones() {
+    let mut state = RuleState::default();
+    state.set_rules(&[rule("a", "true")]);
+    assert_eq!(state.evaluate(&context(50.0)).len(), 1);
+
+    state.set_rules(&[]);
+    assert!(state.is_empty());
+    state.set_rules(&[rule("a", "true")]);
+    assert_eq!(state.evaluate(&context(50.0)).len(), 1);
+  }
+
+  #[test]
+  fn render_message_falls_back_to_default_template() {
+    let mut high = rule("high", "usage > 80");
+    assert_eq!(high.render_message(85.04), "规则「high」已触发，当前使用量 85.0%。");
+    high.message_template = Some("{name}: {condition}".to_string());
+    assert_eq!(high.render_message(85.0), "high: usage > 80");
+  }
+}
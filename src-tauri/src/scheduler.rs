@@ -0,0 +1,596 @@
+//! 定时检查调度辅助逻辑
+//! 负责连续失败统计、失败后的指数退避计算、数据过期判断，
+//! 以及休眠唤醒 / 系统时间跳变后的补偿策略
+
+use rand::Rng;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+/// 失败后首次重试的等待时间（秒）
+const BACKOFF_BASE_SECS: u64 = 60;
+/// 退避指数上限，避免移位溢出
+const BACKOFF_MAX_EXPONENT: u32 = 16;
+/// 随机抖动比例（±20%），避免多实例同时重试
+const BACKOFF_JITTER_RATIO: f64 = 0.2;
+
+/// 等待下一次检查时的心跳周期（秒），每个心跳检测一次时间跳变
+const CLOCK_CHECK_INTERVAL_SECS: u64 = 30;
+/// 墙上时间与单调时间偏差超过该值（秒）视为休眠唤醒或系统时间被修改
+const CLOCK_JUMP_TOLERANCE_SECS: u64 = 60;
+
+/// 时钟抽象
+/// 调度器通过该接口读取时间与睡眠，便于在测试中注入可控时钟
+pub trait Clock: Send + Sync {
+  /// 墙上时间（Unix 毫秒）
+  fn now_millis(&self) -> u64;
+  /// 单调时间（系统休眠期间通常不前进）
+  fn monotonic(&self) -> Instant;
+  /// 睡眠指定时间
+  fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+}
+
+/// 系统时钟
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  fn now_millis(&self) -> u64 {
+    std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .map(|d| d.as_millis() as u64)
+      .unwrap_or(0)
+  }
+
+  fn monotonic(&self) -> Instant {
+    Instant::now()
+  }
+
+  fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+    Box::pin(tokio::time::sleep(duration))
+  }
+}
+
+/// 时间跳变后的补偿策略
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CatchUpPolicy {
+  /// 立即执行一次检查，之后按正常间隔继续
+  #[default]
+  RunImmediately,
+  /// 跳过错过的检查，从现在起重新等待一个完整间隔
+  Skip,
+  /// 按墙上时间重新对齐：原定时间已过则立即执行，否则等到原定时间
+  Realign,
+}
+
+/// 补偿动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatchUpAction {
+  /// 立即执行检查
+  RunNow,
+  /// 继续等待指定时间后执行
+  WaitFor(Duration),
+}
+
+/// 根据补偿策略决定时间跳变后的动作
+/// `due_wall` 为原定下一次检查的墙上时间（Unix 毫秒）
+pub fn catch_up_action(policy: CatchUpPolicy, interval: Duration, now_wall: u64, due_wall: u64) -> CatchUpAction {
+  match policy {
+    CatchUpPolicy::RunImmediately => CatchUpAction::RunNow,
+    CatchUpPolicy::Skip => CatchUpAction::WaitFor(interval),
+    CatchUpPolicy::Realign => {
+      if now_wall >= due_wall {
+        CatchUpAction::RunNow
+      } else {
+        CatchUpAction::WaitFor(Duration::from_millis(due_wall - now_wall))
+      }
+    }
+  }
+}
+
+/// 时间跳变检测器
+/// 比较两次观测之间墙上时间与单调时间的流逝，偏差过大说明经历了休眠或系统时间被修改
+#[derive(Debug, Clone)]
+pub struct ClockJumpDetector {
+  last_wall: u64,
+  last_monotonic: Instant,
+  tolerance_millis: u64,
+}
+
+impl ClockJumpDetector {
+  /// 以当前时间为基准创建检测器
+  pub fn new(clock: &dyn Clock, tolerance: Duration) -> Self {
+    Self {
+      last_wall: clock.now_millis(),
+      last_monotonic: clock.monotonic(),
+      tolerance_millis: tolerance.as_millis() as u64,
+    }
+  }
+
+  /// 观测一次时间并更新基准
+  /// 检测到跳变时返回偏差毫秒数：正数表示墙上时间多走了（休眠唤醒或时间被调快），负数表示时间被调慢
+  pub fn observe(&mut self, clock: &dyn Clock) -> Option<i64> {
+    let wall = clock.now_millis();
+    let monotonic = clock.monotonic();
+
+    let wall_elapsed = wall as i64 - self.last_wall as i64;
+    let monotonic_elapsed = monotonic.saturating_duration_since(self.last_monotonic).as_millis() as i64;
+
+    self.last_wall = wall;
+    self.last_monotonic = monotonic;
+
+    let drift = wall_elapsed - monotonic_elapsed;
+    if drift.unsigned_abs() > self.tolerance_millis {
+      Some(drift)
+    } else {
+      None
+    }
+  }
+}
+
+/// 等待下一次检查
+/// 按心跳周期分段睡眠，每个心跳检测一次时间跳变，检测到后按补偿策略处理，
+/// 避免休眠唤醒后检查被推迟或集中触发
+pub async fn wait_for_next_check(
+  clock: &dyn Clock,
+  delay: Duration,
+  interval: Duration,
+  policy: CatchUpPolicy,
+) {
+  let mut detector = ClockJumpDetector::new(clock, Duration::from_secs(CLOCK_JUMP_TOLERANCE_SECS));
+  let mut remaining = delay;
+  let mut due_wall = clock.now_millis().saturating_add(delay.as_millis() as u64);
+
+  while !remaining.is_zero() {
+    let slice = remaining.min(Duration::from_secs(CLOCK_CHECK_INTERVAL_SECS));
+    let started = clock.monotonic();
+    clock.sleep(slice).await;
+    remaining = remaining.saturating_sub(clock.monotonic().saturating_duration_since(started));
+
+    if let Some(drift) = detector.observe(clock) {
+      let action = catch_up_action(policy, interval, clock.now_millis(), due_wall);
+      log::warn!(
+        "[定时任务] 检测到时间跳变 {} 秒（休眠唤醒或系统时间被修改），补偿策略 {:?} -> {:?}",
+        drift / 1000,
+        policy,
+        action
+      );
+
+      match action {
+        CatchUpAction::RunNow => return,
+        CatchUpAction::WaitFor(wait) => {
+          remaining = wait;
+          due_wall = clock.now_millis().saturating_add(wait.as_millis() as u64);
+        }
+      }
+    }
+  }
+}
+
+/// 连续失败统计
+#[derive(Debug, Default, Clone)]
+pub struct FailureTracker {
+  /// 连续失败次数
+  pub consecutive_failures: u32,
+  /// 本轮失败是否已经升级为错误通知
+  pub escalated: bool,
+  /// 最近一次失败原因
+  pub last_error: Option<String>,
+  /// 最近一次计入失败的页面加载序号，同一次检查的多个错误只计一次
+  counted_seq: Option<u64>,
+}
+
+impl FailureTracker {
+  /// 判断一个错误是否应计入失败次数，并标记其所属检查已计入
+  /// `seq` 为错误所属的页面加载序号，`latest` 为最近一次开始的加载序号：
+  /// 所属检查已计入过、或早于最近一次加载的迟到错误不计入；
+  /// 没有序号的错误（窗口不存在等触发失败）每次触发只上报一次，总是计入
+  pub fn claim(&mut self, seq: Option<u64>, latest: u64) -> bool {
+    let Some(seq) = seq else {
+      return true;
+    };
+    if seq < latest || self.counted_seq.is_some_and(|counted| seq <= counted) {
+      return false;
+    }
+    self.counted_seq = Some(seq);
+    true
+  }
+
+  /// 记录一次失败
+  /// 当连续失败次数达到 `notify_after` 且本轮尚未通知时返回 true
+  pub fn record_failure(&mut self, error: &str, notify_after: u32) -> bool {
+    self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+    self.last_error = Some(error.to_string());
+
+    if !self.escalated && self.consecutive_failures >= notify_after.max(1) {
+      self.escalated = true;
+      return true;
+    }
+    false
+  }
+
+  /// 记录一次成功并清空失败状态
+  /// 如果之前已经发送过错误通知，返回 true，调用方应发送"已恢复"通知
+  pub fn record_success(&mut self) -> bool {
+    let recovered = self.escalated;
+    *self = Self {
+      counted_seq: self.counted_seq,
+      ..Self::default()
+    };
+    recovered
+  }
+}
+
+/// 登录失效状态
+/// 每次登录失效只通知一次，重新获取到数据后才会再次通知；
+/// 失效期间的检查失败是登录失效的表现，不计入连续失败次数
+#[derive(Debug, Default, Clone)]
+pub struct LoginState {
+  /// 是否已检测到登录失效且尚未重新获取到数据
+  required: bool,
+}
+
+impl LoginState {
+  /// 检测到登录失效，返回是否需要通知（本轮失效首次检测到）
+  pub fn mark_required(&mut self) -> bool {
+    !std::mem::replace(&mut self.required, true)
+  }
+
+  /// 重新获取到数据，说明已重新登录
+  pub fn mark_recovered(&mut self) {
+    self.required = false;
+  }
+
+  /// 检查失败是否计入连续失败次数
+  pub fn counts_failures(&self) -> bool {
+    !self.required
+  }
+}
+
+/// 计算下一次检查前的等待时间
+/// 没有失败时使用正常间隔；失败后从 BACKOFF_BASE_SECS 开始按 2 的幂增长，
+/// 上限为正常间隔，并叠加随机抖动
+pub fn next_check_delay(normal: Duration, consecutive_failures: u32) -> Duration {
+  if consecutive_failures == 0 {
+    return normal;
+  }
+
+  let exponent = (consecutive_failures - 1).min(BACKOFF_MAX_EXPONENT);
+  let backoff_secs = BACKOFF_BASE_SECS
+    .saturating_mul(1u64 << exponent)
+    .min(normal.as_secs())
+    .max(1);
+
+  let jitter = rand::thread_rng().gen_range(-BACKOFF_JITTER_RATIO..=BACKOFF_JITTER_RATIO);
+  Duration::from_secs_f64(backoff_secs as f64 * (1.0 + jitter))
+}
+
+/// 数据过期看门狗状态
+#[derive(Debug, Clone)]
+pub struct WatchdogState {
+  /// 看门狗启动时间（Unix 毫秒），尚无任何读数时以此计算数据年龄
+  pub started_at: u64,
+  /// 本轮过期是否已经告警，收到新数据后重置
+  pub alerted: bool,
+}
+
+impl WatchdogState {
+  /// 创建新的看门狗状态
+  pub fn new(started_at: u64) -> Self {
+    Self { started_at, alerted: false }
+  }
+
+  /// 计算数据年龄（毫秒）
+  /// 没有成功读数时从看门狗启动时间开始计算
+  pub fn data_age_millis(&self, last_success: Option<u64>, now: u64) -> u64 {
+    now.saturating_sub(last_success.unwrap_or(self.started_at))
+  }
+
+  /// 检查数据是否过期
+  /// 首次超过 `max_age` 时返回 true，同一轮过期只告警一次；`max_age` 为 0 表示禁用
+  pub fn check(&mut self, last_success: Option<u64>, now: u64, max_age: Duration) -> bool {
+    if max_age.is_zero() || self.alerted {
+      return false;
+    }
+    if self.data_age_millis(last_success, now) > max_age.as_millis() as u64 {
+      self.alerted = true;
+      return true;
+    }
+    false
+  }
+
+  /// 收到新数据时调用，返回之前是否处于已告警状态
+  pub fn reset(&mut self) -> bool {
+    std::mem::replace(&mut self.alerted, false)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn failure_tracker_escalates_once_after_threshold() {
+    let mut tracker = FailureTracker::default();
+    assert!(!tracker.record_failure("a", 3));
+    assert!(!tracker.record_failure("b", 3));
+    assert!(tracker.record_failure("c", 3));
+    assert!(!tracker.record_failure("d", 3));
+    assert_eq!(tracker.consecutive_failures, 4);
+    assert_eq!(tracker.last_error.as_deref(), Some("d"));
+  }
+
+  #[test]
+  fn failure_tracker_treats_zero_threshold_as_one() {
+    let mut tracker = FailureTracker::default();
+    assert!(tracker.record_failure("timeout", 0));
+  }
+
+  #[test]
+  fn failure_tracker_reports_recovery_only_after_escalation() {
+    let mut tracker = FailureTracker::default();
+    tracker.record_failure("a", 2);
+    assert!(!tracker.record_success());
+    assert_eq!(tracker.consecutive_failures, 0);
+
+    tracker.record_failure("a", 1);
+    assert!(tracker.record_success());
+    assert!(!tracker.escalated);
+    assert!(tracker.last_error.is_none());
+  }
+
+  #[test]
+  fn failure_tracker_counts_each_check_once() {
+    let mut tracker = FailureTracker::default();
+    // 同一次加载的提取错误与加载超时只计一次
+    assert!(tracker.claim(Some(3), 3));
+    assert!(!tracker.claim(Some(3), 3));
+    // 新一次检查开始后，上一次加载迟到的错误不计入
+    assert!(!tracker.claim(Some(3), 4));
+    assert!(!tracker.claim(Some(2), 4));
+    assert!(tracker.claim(Some(4), 4));
+    // 没有序号的触发失败每次都计入
+    assert!(tracker.claim(None, 4));
+    assert!(tracker.claim(None, 4));
+  }
+
+  #[test]
+  fn failure_tracker_remembers_counted_check_after_success() {
+    let mut tracker = FailureTracker::default();
+    assert!(tracker.claim(Some(5), 5));
+    tracker.record_failure("a", 3);
+    tracker.record_success();
+    assert!(!tracker.claim(Some(5), 5));
+    assert!(tracker.claim(Some(6), 6));
+  }
+
+  #[test]
+  fn login_state_notifies_once_per_expiry() {
+    let mut login = LoginState::default();
+    assert!(login.counts_failures());
+    assert!(login.mark_required());
+    assert!(!login.mark_required());
+    // 登录失效期间的提取错误不计入失败
+    assert!(!login.counts_failures());
+
+    login.mark_recovered();
+    assert!(login.counts_failures());
+    assert!(login.mark_required());
+  }
+
+  #[test]
+  fn next_check_delay_without_failures_is_normal_interval() {
+    let normal = Duration::from_secs(30 * 60);
+    assert_eq!(next_check_delay(normal, 0), normal);
+  }
+
+  /// 断言时长落在 base 的抖动范围内
+  fn assert_within_jitter(delay: Duration, base_secs: u64) {
+    let base = base_secs as f64;
+    let secs = delay.as_secs_f64();
+    assert!(
+      secs >= base * (1.0 - BACKOFF_JITTER_RATIO) - 1e-6 && secs <= base * (1.0 + BACKOFF_JITTER_RATIO) + 1e-6,
+      "{}s 不在 {}s 的抖动范围内",
+      secs,
+      base
+    );
+  }
+
+  #[test]
+  fn next_check_delay_grows_exponentially() {
+    let normal = Duration::from_secs(24 * 3600);
+    assert_within_jitter(next_check_delay(normal, 1), 60);
+    assert_within_jitter(next_check_delay(normal, 2), 120);
+    assert_within_jitter(next_check_delay(normal, 5), 960);
+  }
+
+  #[test]
+  fn next_check_delay_is_capped_at_normal_interval() {
+    let normal = Duration::from_secs(30 * 60);
+    assert_within_jitter(next_check_delay(normal, 10), 30 * 60);
+    assert_within_jitter(next_check_delay(normal, u32::MAX), 30 * 60);
+  }
+
+  #[test]
+  fn next_check_delay_never_drops_below_one_second() {
+    assert_within_jitter(next_check_delay(Duration::ZERO, 3), 1);
+  }
+
+  #[test]
+  fn watchdog_measures_age_from_start_without_readings() {
+    let watchdog = WatchdogState::new(1_000);
+    assert_eq!(watchdog.data_age_millis(None, 61_000), 60_000);
+    assert_eq!(watchdog.data_age_millis(Some(50_000), 61_000), 11_000);
+    assert_eq!(watchdog.data_age_millis(Some(70_000), 61_000), 0);
+  }
+
+  #[test]
+  fn watchdog_alerts_once_per_stale_period() {
+    let max_age = Duration::from_secs(60);
+    let mut watchdog = WatchdogState::new(0);
+    assert!(!watchdog.check(None, 60_000, max_age));
+    assert!(watchdog.check(None, 60_001, max_age));
+    assert!(!watchdog.check(None, 120_000, max_age));
+
+    assert!(watchdog.reset());
+    assert!(!watchdog.reset());
+    assert!(!watchdog.check(Some(120_000), 150_000, max_age));
+    assert!(watchdog.check(Some(120_000), 180_001, max_age));
+  }
+
+  #[test]
+  fn watchdog_disabled_with_zero_max_age() {
+    let mut watchdog = WatchdogState::new(0);
+    assert!(!watchdog.check(None, u64::MAX, Duration::ZERO));
+  }
+
+  /// 测试时钟
+  /// 睡眠立即返回并推进时间；可以在第 n 次睡眠后让墙上时间额外跳变，模拟休眠唤醒或修改系统时间
+  struct FakeClock {
+    base: Instant,
+    state: std::sync::Mutex<FakeClockState>,
+  }
+
+  #[derive(Default)]
+  struct FakeClockState {
+    wall: u64,
+    monotonic: Duration,
+    sleeps: usize,
+    slept: Duration,
+    /// (第几次睡眠之后, 墙上时间跳变的毫秒数)
+    jumps: Vec<(usize, i64)>,
+  }
+
+  impl FakeClock {
+    fn new(wall: u64, jumps: Vec<(usize, i64)>) -> Self {
+      Self {
+        base: Instant::now(),
+        state: std::sync::Mutex::new(FakeClockState { wall, jumps, ..FakeClockState::default() }),
+      }
+    }
+
+    /// 累计睡眠的时间
+    fn slept(&self) -> Duration {
+      self.state.lock().unwrap().slept
+    }
+  }
+
+  impl Clock for FakeClock {
+    fn now_millis(&self) -> u64 {
+      self.state.lock().unwrap().wall
+    }
+
+    fn monotonic(&self) -> Instant {
+      self.base + self.state.lock().unwrap().monotonic
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+      let mut state = self.state.lock().unwrap();
+      state.sleeps += 1;
+      state.slept += duration;
+      state.monotonic += duration;
+      state.wall += duration.as_millis() as u64;
+      let sleeps = state.sleeps;
+      let jump: i64 = state.jumps.iter().filter(|(after, _)| *after == sleeps).map(|(_, jump)| jump).sum();
+      state.wall = state.wall.saturating_add_signed(jump);
+      Box::pin(std::future::ready(()))
+    }
+  }
+
+  const START: u64 = 1_700_000_000_000;
+  const MINUTE: u64 = 60_000;
+
+  fn minutes(n: u64) -> Duration {
+    Duration::from_secs(n * 60)
+  }
+
+  /// 以 30 分钟间隔等待下一次检查，返回实际睡眠的时间
+  async fn wait_with(policy: CatchUpPolicy, jumps: Vec<(usize, i64)>) -> Duration {
+    let clock = FakeClock::new(START, jumps);
+    wait_for_next_check(&clock, minutes(30), minutes(30), policy).await;
+    clock.slept()
+  }
+
+  #[tokio::test]
+  async fn waits_full_delay_without_clock_jump() {
+    for policy in [CatchUpPolicy::RunImmediately, CatchUpPolicy::Skip, CatchUpPolicy::Realign] {
+      assert_eq!(wait_with(policy, Vec::new()).await, minutes(30));
+    }
+  }
+
+  #[tokio::test]
+  async fn small_wall_drift_is_not_a_jump() {
+    let slept = wait_with(CatchUpPolicy::RunImmediately, vec![(2, CLOCK_JUMP_TOLERANCE_SECS as i64 * 1000)]).await;
+    assert_eq!(slept, minutes(30));
+  }
+
+  #[tokio::test]
+  async fn forward_jump_runs_immediately() {
+    // 第 2 个心跳后休眠唤醒，墙上时间多走了 1 小时
+    let slept = wait_with(CatchUpPolicy::RunImmediately, vec![(2, 60 * MINUTE as i64)]).await;
+    assert_eq!(slept, minutes(1));
+  }
+
+  #[tokio::test]
+  async fn forward_jump_skip_waits_full_interval() {
+    let slept = wait_with(CatchUpPolicy::Skip, vec![(2, 60 * MINUTE as i64)]).await;
+    assert_eq!(slept, minutes(1) + minutes(30));
+  }
+
+  #[tokio::test]
+  async fn forward_jump_past_due_realigns_immediately() {
+    let slept = wait_with(CatchUpPolicy::Realign, vec![(2, 60 * MINUTE as i64)]).await;
+    assert_eq!(slept, minutes(1));
+  }
+
+  #[tokio::test]
+  async fn forward_jump_before_due_realigns_to_wall_clock() {
+    // 唤醒后离原定时间还有 30 - 1 - 5 = 24 分钟
+    let slept = wait_with(CatchUpPolicy::Realign, vec![(2, 5 * MINUTE as i64)]).await;
+    assert_eq!(slept, minutes(1) + minutes(24));
+  }
+
+  #[tokio::test]
+  async fn backward_jump_runs_immediately() {
+    let slept = wait_with(CatchUpPolicy::RunImmediately, vec![(2, -10 * MINUTE as i64)]).await;
+    assert_eq!(slept, minutes(1));
+  }
+
+  #[tokio::test]
+  async fn backward_jump_skip_waits_full_interval() {
+    let slept = wait_with(CatchUpPolicy::Skip, vec![(2, -10 * MINUTE as i64)]).await;
+    assert_eq!(slept, minutes(1) + minutes(30));
+  }
+
+  #[tokio::test]
+  async fn backward_jump_realigns_to_original_due_time() {
+    // 时间被调慢 10 分钟，按墙上时间还要等 30 - 1 + 10 = 39 分钟
+    let slept = wait_with(CatchUpPolicy::Realign, vec![(2, -10 * MINUTE as i64)]).await;
+    assert_eq!(slept, minutes(1) + minutes(39));
+  }
+
+  #[test]
+  fn catch_up_action_per_policy() {
+    let interval = minutes(30);
+    assert_eq!(catch_up_action(CatchUpPolicy::RunImmediately, interval, 10, 5), CatchUpAction::RunNow);
+    assert_eq!(catch_up_action(CatchUpPolicy::Skip, interval, 10, 5), CatchUpAction::WaitFor(interval));
+    assert_eq!(catch_up_action(CatchUpPolicy::Realign, interval, 10, 10), CatchUpAction::RunNow);
+    assert_eq!(
+      catch_up_action(CatchUpPolicy::Realign, interval, 10, 2_010),
+      CatchUpAction::WaitFor(Duration::from_secs(2))
+    );
+  }
+
+  #[tokio::test]
+  async fn jump_detector_reports_signed_drift() {
+    let clock = FakeClock::new(START, vec![(1, 5 * MINUTE as i64), (2, -5 * MINUTE as i64)]);
+    let mut detector = ClockJumpDetector::new(&clock, Duration::from_secs(CLOCK_JUMP_TOLERANCE_SECS));
+    clock.sleep(minutes(1)).await;
+    assert_eq!(detector.observe(&clock), Some(5 * MINUTE as i64));
+    clock.sleep(minutes(1)).await;
+    assert_eq!(detector.observe(&clock), Some(-5 * MINUTE as i64));
+    clock.sleep(minutes(1)).await;
+    assert_eq!(detector.observe(&clock), None);
+  }
+}
@@ -0,0 +1,65 @@
+//! 配置文件的 JSON Schema
+//! 由 AppConfig 的类型定义生成（字段说明取自文档注释），启动时与配置迁移后写入配置目录下的 config.schema.json，
+//! 编辑器与部署脚本可据此校验手工编辑的配置。
+//! Schema 只描述当前程序支持的版本：降级运行旧版本时会写回旧版本的 Schema，
+//! 而由新版本写入的 config.json 保持不变（见 migration::ensure_writable），此时两者可能不一致
+
+use crate::AppConfig;
+use std::path::Path;
+
+/// 生成配置的 JSON Schema
+pub fn settings_schema() -> serde_json::Value {
+  serde_json::to_value(schemars::schema_for!(AppConfig)).unwrap_or(serde_json::Value::Null)
+}
+
+/// 把 JSON Schema 写入文件
+/// 内容与现有文件相同时不写入，返回是否写入了文件
+pub fn write_schema_file(path: &Path) -> Result<bool, String> {
+  let content = serde_json::to_string_pretty(&settings_schema()).map_err(|e| format!("Schema 序列化失败: {}", e))?;
+  if std::fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+    return Ok(false);
+  }
+  crate::storage::write_atomic(path, content.as_bytes()).map_err(|e| format!("写入 Schema 失败: {}", e))?;
+  Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::TempDir;
+  use serde_json::{json, Value};
+
+  fn threshold_schema(schema: &Value) -> &Value {
+    &schema["$defs"]["ThresholdTier"]["properties"]["threshold"]
+  }
+
+  #[test]
+  fn threshold_range_matches_validation() {
+    let schema = settings_schema();
+    let threshold = threshold_schema(&schema);
+    assert_eq!(threshold["exclusiveMinimum"], json!(0));
+    assert_eq!(threshold["maximum"], json!(100));
+    assert!(threshold.get("minimum").is_none());
+  }
+
+  #[test]
+  fn describes_ranges_enums_and_required_channels() {
+    let schema = settings_schema();
+    let check_interval = &schema["properties"]["checkInterval"];
+    assert_eq!(check_interval["minimum"], json!(crate::validation::MIN_CHECK_INTERVAL_MINUTES));
+    assert_eq!(check_interval["maximum"], json!(crate::validation::MAX_CHECK_INTERVAL_MINUTES));
+    assert_eq!(schema["properties"]["language"]["enum"], json!(crate::validation::SUPPORTED_LANGUAGES));
+    assert_eq!(schema["$defs"]["ThresholdTier"]["properties"]["channels"]["minItems"], json!(1));
+    assert_eq!(schema["$defs"]["AlertRule"]["properties"]["channels"]["minItems"], json!(1));
+  }
+
+  #[test]
+  fn schema_file_is_only_rewritten_when_changed() {
+    let dir = TempDir::new();
+    let path = dir.path().join("config.schema.json");
+    assert_eq!(write_schema_file(&path), Ok(true));
+    assert_eq!(write_schema_file(&path), Ok(false));
+    let written: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written, settings_schema());
+  }
+}
@@ -0,0 +1,410 @@
+//! 配置文件读写
+//! 写入时先写临时文件并 fsync，再通过重命名原子替换目标文件，避免崩溃或断电留下半个文件；
+//! 写入期间持有咨询锁，防止多个进程（如将来的命令行工具）同时写入；
+//! 覆盖或删除前把旧文件轮转备份，只保留最近若干份；
+//! 并提供配置文件的变更检测，区分外部修改与本应用自己的写入
+
+use fs4::fs_std::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// 保留的配置备份份数
+pub const BACKUP_KEEP: usize = 5;
+
+/// 备份文件名前缀与后缀，文件名形如 config-1700000000000.json
+const BACKUP_PREFIX: &str = "config-";
+const BACKUP_SUFFIX: &str = ".json";
+
+/// 配置备份信息
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BackupInfo {
+  /// 备份文件名，恢复时使用
+  #[serde(rename = "name")]
+  pub name: String,
+  /// 备份时间（Unix 毫秒）
+  #[serde(rename = "createdAt")]
+  pub created_at: u64,
+  /// 文件大小（字节）
+  #[serde(rename = "size")]
+  pub size: u64,
+}
+
+/// 文件的修改时间与大小
+pub type FileStamp = (SystemTime, u64);
+
+/// 配置文件的变更检测
+/// 修改时间或大小变化后再比较内容：与本应用最近一次写入（或上次读到）的内容相同时不算外部修改，
+/// 本应用自己保存配置、只更新修改时间的写入都不会触发重新加载
+#[derive(Debug, Default)]
+pub struct ChangeDetector {
+  /// 最近一次看到的修改时间与大小，None 表示文件不存在
+  stamp: Option<FileStamp>,
+  /// 已知的文件内容，None 表示文件不存在
+  content: Option<Vec<u8>>,
+}
+
+impl ChangeDetector {
+  pub fn new(stamp: Option<FileStamp>, content: Option<Vec<u8>>) -> Self {
+    Self { stamp, content }
+  }
+
+  /// 记录本应用写入的内容，删除文件时为 None
+  pub fn record_write(&mut self, content: Option<Vec<u8>>) {
+    self.content = content;
+  }
+
+  /// 修改时间或大小是否变化，变化时记住新的取值
+  pub fn stamp_changed(&mut self, stamp: Option<FileStamp>) -> bool {
+    if stamp == self.stamp {
+      return false;
+    }
+    self.stamp = stamp;
+    true
+  }
+
+  /// 内容是否与已知内容不同，不同时记住新内容；返回 true 表示文件被外部修改，需要重新加载
+  pub fn content_changed(&mut self, content: Option<Vec<u8>>) -> bool {
+    if content == self.content {
+      return false;
+    }
+    self.content = content;
+    true
+  }
+}
+
+/// 配置文件锁
+/// 持有期间其他进程无法获取同一把锁，返回值被释放时自动解锁
+pub struct ConfigLock {
+  _file: File,
+}
+
+impl ConfigLock {
+  /// 获取配置文件的排他锁，已被占用时阻塞等待
+  pub fn acquire(config_path: &Path) -> io::Result<Self> {
+    let file = OpenOptions::new()
+      .create(true)
+      .read(true)
+      .write(true)
+      .truncate(false)
+      .open(config_path.with_extension("json.lock"))?;
+    file.lock_exclusive()?;
+    Ok(Self { _file: file })
+  }
+}
+
+/// 原子写入文件：写临时文件、fsync、重命名
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+  let tmp_path = path.with_extension("json.tmp");
+  {
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+  }
+  if let Err(e) = fs::rename(&tmp_path, path) {
+    let _ = fs::remove_file(&tmp_path);
+    return Err(e);
+  }
+  sync_parent_dir(path);
+  Ok(())
+}
+
+/// 原子写入新文件，目标已存在时保留原文件不写入；返回是否写入
+pub fn write_new_atomic(path: &Path, contents: &[u8]) -> io::Result<bool> {
+  if path.exists() {
+    return Ok(false);
+  }
+  write_atomic(path, contents)?;
+  Ok(true)
+}
+
+/// 同步目录项，保证重命名在断电后仍然生效（仅 Unix 支持打开目录）
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) {
+  if let Some(dir) = path.parent() {
+    if let Ok(dir) = File::open(dir) {
+      let _ = dir.sync_all();
+    }
+  }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) {}
+
+/// 从备份文件名解析备份时间，不是备份文件时返回 None
+fn parse_backup_name(name: &str) -> Option<u64> {
+  name
+    .strip_prefix(BACKUP_PREFIX)?
+    .strip_suffix(BACKUP_SUFFIX)?
+    .parse()
+    .ok()
+}
+
+/// 列出备份，按时间从新到旧排列；备份目录不存在时返回空列表
+pub fn list_backups(backup_dir: &Path) -> io::Result<Vec<BackupInfo>> {
+  let entries = match fs::read_dir(backup_dir) {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(e),
+  };
+
+  let mut backups: Vec<BackupInfo> = entries
+    .filter_map(Result::ok)
+    .filter_map(|entry| {
+      let name = entry.file_name().into_string().ok()?;
+      let created_at = parse_backup_name(&name)?;
+      let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+      Some(BackupInfo { name, created_at, size })
+    })
+    .collect();
+  backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+  Ok(backups)
+}
+
+/// 根据备份文件名得到备份路径
+/// 只接受本模块生成的文件名，避免通过文件名访问备份目录以外的文件
+pub fn backup_path(backup_dir: &Path, name: &str) -> Option<PathBuf> {
+  parse_backup_name(name)?;
+  Some(backup_dir.join(name))
+}
+
+/// 备份当前配置文件，并删除超出 `keep` 份的旧备份
+/// 配置文件不存在时返回 Ok(None)
+fn backup_current(config_path: &Path, dir: &Path, now_millis: u64, keep: usize) -> io::Result<Option<PathBuf>> {
+  if !config_path.exists() {
+    return Ok(None);
+  }
+
+  fs::create_dir_all(dir)?;
+  // 时间戳不早于已有的最新备份，保证同一毫秒内多次备份或系统时间回拨时仍按顺序排列
+  let newest = list_backups(dir)?.first().map_or(0, |backup| backup.created_at + 1);
+  let timestamp = now_millis.max(newest);
+  let path = dir.join(format!("{}{}{}", BACKUP_PREFIX, timestamp, BACKUP_SUFFIX));
+  fs::copy(config_path, &path)?;
+
+  for old in list_backups(dir)?.into_iter().skip(keep.max(1)) {
+    if let Err(e) = fs::remove_file(dir.join(&old.name)) {
+      log::warn!("删除旧配置备份 {} 失败: {}", old.name, e);
+    }
+  }
+  Ok(Some(path))
+}
+
+/// 保存配置文件：加锁、把旧文件备份到 `backup_dir`、原子写入，返回写入的内容
+/// 写入的内容由 `render` 根据持有锁期间读到的现有文件（不存在时为 None）生成，`render` 返回错误时不写入
+pub fn save_config(
+  config_path: &Path,
+  backup_dir: &Path,
+  now_millis: u64,
+  keep: usize,
+  render: impl FnOnce(Option<&[u8]>) -> io::Result<Vec<u8>>,
+) -> io::Result<Vec<u8>> {
+  let _lock = ConfigLock::acquire(config_path)?;
+  let existing = match fs::read(config_path) {
+    Ok(existing) => Some(existing),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+    Err(e) => return Err(e),
+  };
+  let contents = render(existing.as_deref())?;
+  backup_current(config_path, backup_dir, now_millis, keep)?;
+  write_atomic(config_path, &contents)?;
+  Ok(contents)
+}
+
+/// 删除配置文件：加锁、把旧文件备份到 `backup_dir` 后删除；文件不存在时视为成功
+pub fn remove_config(config_path: &Path, backup_dir: &Path, now_millis: u64, keep: usize) -> io::Result<()> {
+  let _lock = ConfigLock::acquire(config_path)?;
+  backup_current(config_path, backup_dir, now_millis, keep)?;
+  match fs::remove_file(config_path) {
+    Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+    _ => Ok(()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::TempDir;
+
+  fn config_path(dir: &TempDir) -> PathBuf {
+    dir.path().join("config.json")
+  }
+
+  fn backup_dir(dir: &TempDir) -> PathBuf {
+    dir.path().join("backups")
+  }
+
+  fn save(dir: &TempDir, contents: &str, now: u64, keep: usize) -> io::Result<()> {
+    save_config(&config_path(dir), &backup_dir(dir), now, keep, |_| Ok(contents.as_bytes().to_vec())).map(|_| ())
+  }
+
+  fn backup_times(dir: &TempDir) -> Vec<u64> {
+    list_backups(&backup_dir(dir)).unwrap().into_iter().map(|b| b.created_at).collect()
+  }
+
+  #[test]
+  fn write_atomic_replaces_file_without_leftovers() {
+    let dir = TempDir::new();
+    let path = config_path(&dir);
+    write_atomic(&path, b"first").unwrap();
+    write_atomic(&path, b"second").unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"second");
+    assert!(!path.with_extension("json.tmp").exists());
+  }
+
+  #[test]
+  fn write_new_atomic_keeps_existing_file() {
+    let dir = TempDir::new();
+    let path = dir.path().join("config.v1.bak.json");
+    assert!(write_new_atomic(&path, b"original").unwrap());
+    assert!(!write_new_atomic(&path, b"later").unwrap());
+    assert_eq!(fs::read(&path).unwrap(), b"original");
+  }
+
+  #[test]
+  fn first_save_has_nothing_to_back_up() {
+    let dir = TempDir::new();
+    save(&dir, "{}", 1_000, BACKUP_KEEP).unwrap();
+    assert_eq!(fs::read_to_string(config_path(&dir)).unwrap(), "{}");
+    assert!(backup_times(&dir).is_empty());
+  }
+
+  #[test]
+  fn save_backs_up_previous_contents() {
+    let dir = TempDir::new();
+    save(&dir, "old", 1_000, BACKUP_KEEP).unwrap();
+    save(&dir, "new", 2_000, BACKUP_KEEP).unwrap();
+    assert_eq!(fs::read_to_string(config_path(&dir)).unwrap(), "new");
+    let backups = list_backups(&backup_dir(&dir)).unwrap();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(backups[0].created_at, 2_000);
+    assert_eq!(fs::read_to_string(backup_dir(&dir).join(&backups[0].name)).unwrap(), "old");
+  }
+
+  #[test]
+  fn old_backups_are_rotated() {
+    let dir = TempDir::new();
+    for i in 0..6 {
+      save(&dir, &i.to_string(), 1_000 * (i + 1), 3).unwrap();
+    }
+    assert_eq!(backup_times(&dir), vec![6_000, 5_000, 4_000]);
+  }
+
+  #[test]
+  fn backup_order_survives_clock_going_backwards() {
+    let dir = TempDir::new();
+    save(&dir, "a", 5_000, BACKUP_KEEP).unwrap();
+    save(&dir, "b", 5_000, BACKUP_KEEP).unwrap();
+    save(&dir, "c", 5_000, BACKUP_KEEP).unwrap();
+    save(&dir, "d", 1_000, BACKUP_KEEP).unwrap();
+    assert_eq!(backup_times(&dir), vec![5_002, 5_001, 5_000]);
+    let newest = &list_backups(&backup_dir(&dir)).unwrap()[0];
+    assert_eq!(fs::read_to_string(backup_dir(&dir).join(&newest.name)).unwrap(), "c");
+  }
+
+  #[test]
+  fn render_sees_existing_contents_and_can_refuse() {
+    let dir = TempDir::new();
+    save(&dir, "old", 1_000, BACKUP_KEEP).unwrap();
+
+    let result = save_config(&config_path(&dir), &backup_dir(&dir), 2_000, BACKUP_KEEP, |existing| {
+      assert_eq!(existing, Some(&b"old"[..]));
+      Err(io::Error::other("refused"))
+    });
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(config_path(&dir)).unwrap(), "old");
+    assert!(backup_times(&dir).is_empty());
+
+    save_config(&config_path(&dir), &backup_dir(&dir), 3_000, BACKUP_KEEP, |existing| {
+      Ok([existing.unwrap_or_default(), b"+new"].concat())
+    })
+    .unwrap();
+    assert_eq!(fs::read_to_string(config_path(&dir)).unwrap(), "old+new");
+  }
+
+  #[test]
+  fn remove_backs_up_and_deletes() {
+    let dir = TempDir::new();
+    remove_config(&config_path(&dir), &backup_dir(&dir), 1_000, BACKUP_KEEP).unwrap();
+    assert!(backup_times(&dir).is_empty());
+
+    save(&dir, "old", 1_000, BACKUP_KEEP).unwrap();
+    remove_config(&config_path(&dir), &backup_dir(&dir), 2_000, BACKUP_KEEP).unwrap();
+    assert!(!config_path(&dir).exists());
+    assert_eq!(backup_times(&dir), vec![2_000]);
+  }
+
+  #[test]
+  fn list_backups_ignores_other_files() {
+    let dir = TempDir::new();
+    assert!(list_backups(&backup_dir(&dir)).unwrap().is_empty());
+    fs::create_dir_all(backup_dir(&dir)).unwrap();
+    fs::write(backup_dir(&dir).join("notes.txt"), "x").unwrap();
+    fs::write(backup_dir(&dir).join("config-abc.json"), "x").unwrap();
+    fs::write(backup_dir(&dir).join("config-42.json"), "x").unwrap();
+    assert_eq!(backup_times(&dir), vec![42]);
+  }
+
+  #[test]
+  fn backup_path_only_accepts_backup_names() {
+    let dir = Path::new("/backups");
+    assert_eq!(backup_path(dir, "config-42.json"), Some(dir.join("config-42.json")));
+    assert_eq!(backup_path(dir, "../config.json"), None);
+    assert_eq!(backup_path(dir, "config-../../etc/passwd.json"), None);
+    assert_eq!(backup_path(dir, "config-42.json.tmp"), None);
+  }
+
+  #[test]
+  fn save_returns_written_contents() {
+    let dir = TempDir::new();
+    let written = save_config(&config_path(&dir), &backup_dir(&dir), 1_000, BACKUP_KEEP, |_| Ok(b"{}".to_vec())).unwrap();
+    assert_eq!(written, b"{}");
+  }
+
+  fn stamp(secs: u64, len: u64) -> Option<FileStamp> {
+    Some((SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs), len))
+  }
+
+  #[test]
+  fn own_writes_are_not_external_changes() {
+    let mut detector = ChangeDetector::new(stamp(1, 2), Some(b"{}".to_vec()));
+    assert!(!detector.stamp_changed(stamp(1, 2)));
+
+    // 本应用写入后，监听到的元数据变化对应的内容与记录的一致
+    detector.record_write(Some(b"{\"a\":1}".to_vec()));
+    assert!(detector.stamp_changed(stamp(2, 7)));
+    assert!(!detector.content_changed(Some(b"{\"a\":1}".to_vec())));
+
+    // 之后的外部修改仍会被发现，同一内容只报告一次
+    assert!(detector.stamp_changed(stamp(3, 7)));
+    assert!(detector.content_changed(Some(b"{\"a\":2}".to_vec())));
+    assert!(!detector.content_changed(Some(b"{\"a\":2}".to_vec())));
+  }
+
+  #[test]
+  fn removal_is_detected_unless_recorded() {
+    let mut detector = ChangeDetector::new(stamp(1, 2), Some(b"{}".to_vec()));
+    assert!(detector.stamp_changed(None));
+    assert!(detector.content_changed(None));
+
+    let mut detector = ChangeDetector::new(stamp(1, 2), Some(b"{}".to_vec()));
+    detector.record_write(None);
+    assert!(detector.stamp_changed(None));
+    assert!(!detector.content_changed(None));
+  }
+
+  #[test]
+  fn touched_file_with_same_contents_is_ignored() {
+    let mut detector = ChangeDetector::new(stamp(1, 2), Some(b"{}".to_vec()));
+    assert!(detector.stamp_changed(stamp(5, 2)));
+    assert!(!detector.content_changed(Some(b"{}".to_vec())));
+  }
+
+  #[test]
+  fn lock_is_released_on_drop() {
+    let dir = TempDir::new();
+    drop(ConfigLock::acquire(&config_path(&dir)).unwrap());
+    let _lock = ConfigLock::acquire(&config_path(&dir)).unwrap();
+  }
+}
@@ -0,0 +1,44 @@
+//! 单元测试共用的辅助工具
+
+use crate::AppConfig;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 本机配置中的企业微信 Webhook 地址
+pub const WEBHOOK: &str = "https://qyapi.weixin.qq.com/cgi-bin/webhook/send?key=local";
+
+/// 本机配置：带有 Webhook 地址与当前方案，检查间隔不是默认值
+pub fn local_config() -> AppConfig {
+  AppConfig {
+    wechat_work_webhook_url: WEBHOOK.to_string(),
+    active_profile: Some("work".to_string()),
+    check_interval: 5,
+    ..AppConfig::default()
+  }
+}
+
+/// 测试用的临时目录，创建时为空，离开作用域时删除
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+  pub fn new() -> Self {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let path = std::env::temp_dir().join(format!(
+      "minmax-test-{}-{}",
+      std::process::id(),
+      COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&path).unwrap();
+    Self(path)
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.0
+  }
+}
+
+impl Drop for TempDir {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_dir_all(&self.0);
+  }
+}
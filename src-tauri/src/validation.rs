@@ -0,0 +1,412 @@
+//! 配置校验
+//! 保存、导入、恢复配置前检查各字段的取值范围，返回按字段区分的错误（附错误代码），前端可据此标记对应的表单项并显示本地化文案；
+//! 加载配置文件时对无效字段做修正而不是拒绝整个文件
+//! 字段路径与配置文件中的 JSON 键一致，如 `checkInterval`、`thresholdTiers[1].threshold`
+
+use crate::AppConfig;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// 检查间隔下限（分钟）
+pub const MIN_CHECK_INTERVAL_MINUTES: i32 = 1;
+/// 检查间隔上限（分钟），即一天
+pub const MAX_CHECK_INTERVAL_MINUTES: i32 = 24 * 60;
+/// 支持的界面语言
+pub const SUPPORTED_LANGUAGES: &[&str] = &["zh", "en"];
+
+/// 单个字段的校验错误
+/// `code` 与 `params` 供前端显示本地化文案，`message` 为中文说明，用于日志与前端不认识的代码
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct FieldError {
+  /// 字段路径
+  #[serde(rename = "field")]
+  pub field: String,
+  /// 错误代码，如 range、channelsRequired
+  #[serde(rename = "code")]
+  pub code: &'static str,
+  /// 错误代码的参数，如取值范围的 min / max
+  #[serde(rename = "params", skip_serializing_if = "BTreeMap::is_empty")]
+  pub params: BTreeMap<&'static str, String>,
+  /// 错误说明
+  #[serde(rename = "message")]
+  pub message: String,
+}
+
+impl FieldError {
+  pub fn new(field: impl Into<String>, code: &'static str, message: impl Into<String>) -> Self {
+    Self {
+      field: field.into(),
+      code,
+      params: BTreeMap::new(),
+      message: message.into(),
+    }
+  }
+
+  /// 附加错误代码的参数
+  pub fn with_param(mut self, name: &'static str, value: impl ToString) -> Self {
+    self.params.insert(name, value.to_string());
+    self
+  }
+}
+
+/// 收集校验错误
+#[derive(Default)]
+struct Errors(Vec<FieldError>);
+
+impl Errors {
+  fn push(&mut self, error: FieldError) {
+    self.0.push(error);
+  }
+
+  /// 检查百分比是否在 [min, 100] 之间
+  fn percent(&mut self, field: impl Into<String>, value: f64, min: f64) {
+    if !value.is_finite() || value < min || value > 100.0 {
+      self.push(range_error(field, min, 100.0, ""));
+    }
+  }
+
+  /// 检查整数是否大于 0
+  fn positive(&mut self, field: &str, value: u32) {
+    if value == 0 {
+      self.push(FieldError::new(field, "positive", "必须大于 0"));
+    }
+  }
+
+  /// 检查是否至少选择了一个通知渠道
+  fn channels<T>(&mut self, field: String, channels: &[T]) {
+    if channels.is_empty() {
+      self.push(FieldError::new(field, "channelsRequired", "至少选择一个通知渠道"));
+    }
+  }
+}
+
+/// 取值超出 [min, max] 的错误，`unit` 为说明中附加的单位
+fn range_error(field: impl Into<String>, min: impl ToString, max: impl ToString, unit: &str) -> FieldError {
+  let (min, max) = (min.to_string(), max.to_string());
+  FieldError::new(field, "range", format!("必须在 {} 到 {} {}之间", min, max, unit))
+    .with_param("min", min)
+    .with_param("max", max)
+}
+
+/// 校验企业微信 Webhook 地址，空字符串表示未配置
+fn validate_webhook_url(field: &str, url: &str) -> Result<(), FieldError> {
+  if url.trim().is_empty() {
+    return Ok(());
+  }
+  let parsed = reqwest::Url::parse(url.trim())
+    .map_err(|e| FieldError::new(field, "invalidUrl", format!("URL 格式无效: {}", e)).with_param("reason", e))?;
+  if !matches!(parsed.scheme(), "http" | "https") {
+    return Err(FieldError::new(field, "urlScheme", "URL 必须以 http:// 或 https:// 开头"));
+  }
+  if parsed.host_str().is_none() {
+    return Err(FieldError::new(field, "urlHost", "URL 缺少主机名"));
+  }
+  Ok(())
+}
+
+/// 校验配置
+/// 全部通过时返回 Ok，否则返回所有字段的错误
+pub fn validate_config(config: &AppConfig) -> Result<(), Vec<FieldError>> {
+  let mut errors = Errors::default();
+
+  // 同一阈值的多个级别会同时触发，其中较低的级别永远不会单独通知
+  let mut thresholds = HashSet::new();
+  for (i, tier) in config.threshold_tiers.iter().enumerate() {
+    let field = format!("thresholdTiers[{}].threshold", i);
+    if !tier.threshold.is_finite() || tier.threshold <= 0.0 || tier.threshold > 100.0 {
+      errors.push(
+        FieldError::new(field, "exclusiveRange", "必须大于 0 且不超过 100")
+          .with_param("min", 0)
+          .with_param("max", 100),
+      );
+    } else if !thresholds.insert(tier.threshold.to_bits()) {
+      errors.push(
+        FieldError::new(field, "duplicateThreshold", format!("阈值重复: {}%", tier.threshold))
+          .with_param("value", tier.threshold),
+      );
+    }
+    errors.channels(format!("thresholdTiers[{}].channels", i), &tier.channels);
+  }
+
+  if !(MIN_CHECK_INTERVAL_MINUTES..=MAX_CHECK_INTERVAL_MINUTES).contains(&config.check_interval) {
+    errors.push(range_error(
+      "checkInterval",
+      MIN_CHECK_INTERVAL_MINUTES,
+      MAX_CHECK_INTERVAL_MINUTES,
+      "分钟",
+    ));
+  }
+
+  if let Err(e) = validate_webhook_url("wechatWorkWebhookUrl", &config.wechat_work_webhook_url) {
+    errors.push(e);
+  }
+
+  if !SUPPORTED_LANGUAGES.contains(&config.language.as_str()) {
+    errors.push(
+      FieldError::new(
+        "language",
+        "unsupportedLanguage",
+        format!("不支持的语言 {}，可选: {}", config.language, SUPPORTED_LANGUAGES.join(", ")),
+      )
+      .with_param("value", &config.language)
+      .with_param("options", SUPPORTED_LANGUAGES.join(", ")),
+    );
+  }
+
+  if let Some(name) = &config.active_profile {
+    if let Err(e) = crate::profiles::validate_name(name) {
+      errors.push(FieldError::new("activeProfile", "invalidProfileName", e));
+    }
+  }
+
+  errors.positive("pageLoadTimeoutSecs", config.page_load_timeout_secs);
+  errors.positive("forecastWindowMinutes", config.forecast_window_minutes);
+  errors.positive("anomalyWindowMinutes", config.anomaly_window_minutes);
+
+  errors.percent("alertHysteresis", config.alert_hysteresis, 0.0);
+  errors.percent("unusedQuotaNudgePercent", config.unused_quota_nudge_percent, 0.0);
+  if !config.anomaly_rate_per_minute.is_finite() || config.anomaly_rate_per_minute < 0.0 {
+    errors.push(FieldError::new("anomalyRatePerMinute", "nonNegative", "不能为负数"));
+  }
+
+  for (i, step) in config.critical_escalation.iter().enumerate() {
+    errors.channels(format!("criticalEscalation[{}]", i), step);
+  }
+
+  let mut rule_names = HashSet::new();
+  for (i, rule) in config.alert_rules.iter().enumerate() {
+    let field = format!("alertRules[{}].name", i);
+    if rule.name.trim().is_empty() {
+      errors.push(FieldError::new(field, "ruleNameRequired", "规则名称不能为空"));
+    } else if !rule_names.insert(rule.name.as_str()) {
+      errors.push(
+        FieldError::new(field, "duplicateRuleName", format!("规则名称重复: {}", rule.name)).with_param("name", &rule.name),
+      );
+    }
+    if let Err(e) = crate::rules::Condition::parse(&rule.condition) {
+      errors.push(
+        FieldError::new(format!("alertRules[{}].condition", i), "invalidCondition", &e).with_param("detail", e),
+      );
+    }
+    errors.channels(format!("alertRules[{}].channels", i), &rule.channels);
+  }
+
+  if errors.0.is_empty() {
+    Ok(())
+  } else {
+    Err(errors.0)
+  }
+}
+
+/// 字段路径拆分为顶层字段名与数组下标，如 `alertRules[2].name` -> (`alertRules`, Some(2))
+pub fn split_field(field: &str) -> (&str, Option<usize>) {
+  let end = field.find(['[', '.']).unwrap_or(field.len());
+  let index = field[end..]
+    .strip_prefix('[')
+    .and_then(|rest| rest.split(']').next())
+    .and_then(|index| index.parse().ok());
+  (&field[..end], index)
+}
+
+/// 修正加载的配置
+/// 配置文件可能被手工编辑或由旧版本写入，校验失败时不拒绝整个文件：
+/// 数组中无效的元素被移除，其他无效字段恢复为默认值；返回修正后的配置与被修正的错误
+pub fn sanitize(config: AppConfig) -> (AppConfig, Vec<FieldError>) {
+  let Err(errors) = validate_config(&config) else {
+    return (config, Vec::new());
+  };
+
+  let (Ok(serde_json::Value::Object(mut fields)), Ok(serde_json::Value::Object(defaults))) =
+    (serde_json::to_value(&config), serde_json::to_value(AppConfig::default()))
+  else {
+    return (AppConfig::default(), errors);
+  };
+
+  // 同一数组的下标从大到小移除，避免移除后下标错位
+  let mut removed: BTreeSet<(&str, std::cmp::Reverse<usize>)> = BTreeSet::new();
+  for error in &errors {
+    match split_field(&error.field) {
+      (field, Some(index)) => {
+        removed.insert((field, std::cmp::Reverse(index)));
+      }
+      (field, None) => {
+        if let Some(default) = defaults.get(field) {
+          fields.insert(field.to_string(), default.clone());
+        }
+      }
+    }
+  }
+  for (field, std::cmp::Reverse(index)) in removed {
+    if let Some(serde_json::Value::Array(items)) = fields.get_mut(field) {
+      if index < items.len() {
+        items.remove(index);
+      }
+    }
+  }
+
+  let config = serde_json::from_value(serde_json::Value::Object(fields)).unwrap_or_default();
+  (config, errors)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::alerts::{all_channels, Severity, ThresholdTier};
+  use crate::rules::AlertRule;
+
+  fn rule(name: &str, condition: &str) -> AlertRule {
+    AlertRule {
+      name: name.to_string(),
+      condition: condition.to_string(),
+      severity: Severity::Warning,
+      channels: all_channels(),
+      message_template: None,
+      enabled: true,
+    }
+  }
+
+  fn codes(config: &AppConfig) -> Vec<(String, &'static str)> {
+    validate_config(config)
+      .err()
+      .unwrap_or_default()
+      .into_iter()
+      .map(|e| (e.field, e.code))
+      .collect()
+  }
+
+  #[test]
+  fn default_config_is_valid() {
+    assert!(validate_config(&AppConfig::default()).is_ok());
+  }
+
+  #[test]
+  fn threshold_must_be_above_zero() {
+    let config = AppConfig {
+      threshold_tiers: vec![
+        ThresholdTier::new(0.0, Severity::Info, all_channels()),
+        ThresholdTier::new(100.0, Severity::Critical, Vec::new()),
+        ThresholdTier::new(f64::NAN, Severity::Warning, all_channels()),
+      ],
+      ..AppConfig::default()
+    };
+    assert_eq!(
+      codes(&config),
+      vec![
+        ("thresholdTiers[0].threshold".to_string(), "exclusiveRange"),
+        ("thresholdTiers[1].channels".to_string(), "channelsRequired"),
+        ("thresholdTiers[2].threshold".to_string(), "exclusiveRange"),
+      ]
+    );
+  }
+
+  #[test]
+  fn duplicate_thresholds_are_rejected() {
+    let config = AppConfig {
+      threshold_tiers: vec![
+        ThresholdTier::new(92.2, Severity::Warning, all_channels()),
+        ThresholdTier::new(92.25, Severity::Critical, all_channels()),
+        ThresholdTier::new(92.2, Severity::Critical, all_channels()),
+      ],
+      ..AppConfig::default()
+    };
+    let errors = validate_config(&config).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "thresholdTiers[2].threshold");
+    assert_eq!(errors[0].code, "duplicateThreshold");
+    assert_eq!(errors[0].params["value"], "92.2");
+  }
+
+  #[test]
+  fn check_interval_range_has_params() {
+    let config = AppConfig {
+      check_interval: 0,
+      ..AppConfig::default()
+    };
+    let errors = validate_config(&config).unwrap_err();
+    assert_eq!(errors[0].code, "range");
+    assert_eq!(errors[0].params["min"], MIN_CHECK_INTERVAL_MINUTES.to_string());
+    assert_eq!(errors[0].params["max"], MAX_CHECK_INTERVAL_MINUTES.to_string());
+    assert!(validate_config(&AppConfig {
+      check_interval: MAX_CHECK_INTERVAL_MINUTES,
+      ..AppConfig::default()
+    })
+    .is_ok());
+  }
+
+  #[test]
+  fn webhook_url_errors() {
+    let code = |url: &str| validate_webhook_url("url", url).err().map(|e| e.code);
+    assert_eq!(code(""), None);
+    assert_eq!(code("https://qyapi.weixin.qq.com/cgi-bin/webhook/send?key=x"), None);
+    assert_eq!(code("not a url"), Some("invalidUrl"));
+    assert_eq!(code("ftp://example.com"), Some("urlScheme"));
+    assert_eq!(code("http://"), Some("invalidUrl"));
+  }
+
+  #[test]
+  fn unsupported_language() {
+    let config = AppConfig {
+      language: "fr".to_string(),
+      ..AppConfig::default()
+    };
+    let errors = validate_config(&config).unwrap_err();
+    assert_eq!(errors[0].code, "unsupportedLanguage");
+    assert_eq!(errors[0].params["value"], "fr");
+  }
+
+  #[test]
+  fn rule_errors() {
+    let config = AppConfig {
+      alert_rules: vec![rule("a", "usage > 80"), rule("a", "usage >"), rule(" ", "usage > 1")],
+      ..AppConfig::default()
+    };
+    assert_eq!(
+      codes(&config),
+      vec![
+        ("alertRules[1].name".to_string(), "duplicateRuleName"),
+        ("alertRules[1].condition".to_string(), "invalidCondition"),
+        ("alertRules[2].name".to_string(), "ruleNameRequired"),
+      ]
+    );
+  }
+
+  #[test]
+  fn split_field_paths() {
+    assert_eq!(split_field("checkInterval"), ("checkInterval", None));
+    assert_eq!(split_field("alertRules[12].name"), ("alertRules", Some(12)));
+    assert_eq!(split_field("criticalEscalation[0]"), ("criticalEscalation", Some(0)));
+  }
+
+  #[test]
+  fn sanitize_keeps_valid_config() {
+    let config = AppConfig {
+      check_interval: 5,
+      ..AppConfig::default()
+    };
+    let (sanitized, errors) = sanitize(config);
+    assert!(errors.is_empty());
+    assert_eq!(sanitized.check_interval, 5);
+  }
+
+  #[test]
+  fn sanitize_drops_invalid_items_and_resets_fields() {
+    let config = AppConfig {
+      check_interval: 0,
+      language: "en".to_string(),
+      alert_rules: vec![rule("bad", "usage >"), rule("ok", "usage > 80"), rule("ok", "usage > 90")],
+      threshold_tiers: vec![
+        ThresholdTier::new(50.0, Severity::Info, all_channels()),
+        ThresholdTier::new(150.0, Severity::Critical, all_channels()),
+      ],
+      ..AppConfig::default()
+    };
+    let (sanitized, errors) = sanitize(config);
+    assert_eq!(errors.len(), 4);
+    assert_eq!(sanitized.check_interval, AppConfig::default().check_interval);
+    assert_eq!(sanitized.language, "en");
+    assert_eq!(sanitized.threshold_tiers.len(), 1);
+    assert_eq!(sanitized.threshold_tiers[0].threshold, 50.0);
+    assert_eq!(sanitized.alert_rules.len(), 1);
+    assert_eq!(sanitized.alert_rules[0].condition, "usage > 80");
+    assert!(validate_config(&sanitized).is_ok());
+  }
+}
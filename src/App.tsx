@@ -10,7 +10,7 @@ import { MonitorPanel } from './components/MonitorPanel';
 import { ConfirmModal } from './components/ConfirmModal';
 
 // 导入 hook
-import { useUsage, Settings } from './hooks/useUsage';
+import { useUsage, Settings, ThresholdTier, Severity, ActiveSnooze, getLowestThreshold } from './hooks/useUsage';
 import { useMinMaxWebview } from './hooks/useMinMaxWebview';
 import { useNotification } from './hooks/useNotification';
 
@@ -22,19 +22,104 @@ type Language = Lang;
 
 // 设置数据接口（完整定义，确保与后端 AppConfig 对应）
 interface AppSettings {
-  warningThreshold: number;
+  thresholdTiers: ThresholdTier[];
   checkInterval: number;
   wechatWorkWebhookUrl: string;
   language: Language;
+  // 连续失败多少次后发送错误通知（界面暂未提供编辑入口）
+  failureNotifyAfter?: number;
+  // 超过多少分钟没有新数据时告警，0 表示禁用（界面暂未提供编辑入口）
+  staleAfterMinutes?: number;
+  // 检测到登录失效时是否自动显示 MiniMax 窗口（界面暂未提供编辑入口）
+  openWindowOnLoginRequired?: boolean;
+  // 页面加载超时时间（秒），超时后重建 MiniMax 窗口（界面暂未提供编辑入口）
+  pageLoadTimeoutSecs?: number;
+  // 隐藏窗口定期重建周期（小时），0 表示禁用（界面暂未提供编辑入口）
+  webviewRecycleHours?: number;
+  // 休眠唤醒后的补偿策略（界面暂未提供编辑入口）
+  catchUpPolicy?: 'runImmediately' | 'skip' | 'realign';
+  // 告警回差（百分点）（界面暂未提供编辑入口）
+  alertHysteresis?: number;
+  // 持续超过阈值时的重复通知冷却时间（分钟），0 表示不重复（界面暂未提供编辑入口）
+  alertCooldownMinutes?: number;
+  // 检测到配额重置时是否通知（界面暂未提供编辑入口）
+  notifyQuotaReset?: boolean;
+  // 使用量回落到阈值以下时是否通知（界面暂未提供编辑入口）
+  notifyRecovery?: boolean;
+  // 预计在重置前用满时是否告警（界面暂未提供编辑入口）
+  forecastAlert?: boolean;
+  // 趋势拟合使用的时间窗口（分钟）（界面暂未提供编辑入口）
+  forecastWindowMinutes?: number;
+  // 配额重置前多少分钟提醒，0 表示禁用（界面暂未提供编辑入口）
+  preResetReminderMinutes?: number;
+  // 配额重置前多少分钟检查未用配额，0 表示禁用（界面暂未提供编辑入口）
+  unusedQuotaNudgeMinutes?: number;
+  // 使用量低于该值时提醒配额未充分使用（界面暂未提供编辑入口）
+  unusedQuotaNudgePercent?: number;
+  // 严重告警重复提醒间隔（分钟），0 表示不重复（界面暂未提供编辑入口）
+  criticalRepeatMinutes?: number;
+  // 严重告警升级步骤，每一步为一组通知渠道：第一步对应首次告警，第 n 次重复提醒使用第 n + 1 步
+  // 只支持系统通知与企业微信，不支持邮件（界面暂未提供编辑入口）
+  criticalEscalation?: Array<Array<'system' | 'wechatWork'>>;
+  // 自定义告警规则（界面暂未提供编辑入口）
+  alertRules?: Array<{
+    name: string;
+    condition: string;
+    severity?: Severity;
+    channels?: Array<'system' | 'wechatWork'>;
+    messageTemplate?: string;
+    enabled?: boolean;
+  }>;
+  // 异常消耗上限（百分点 / 分钟），0 表示禁用（界面暂未提供编辑入口）
+  anomalyRatePerMinute?: number;
+  // 异常消耗检测回看的时间窗口（分钟）（界面暂未提供编辑入口）
+  anomalyWindowMinutes?: number;
+  // 异常消耗告警级别，按该级别阈值的渠道发送（界面暂未提供编辑入口）
+  anomalySeverity?: Severity;
+  // 配置版本（由后端维护）
+  version?: number;
+  // 当前生效的配置方案名称
+  activeProfile?: string | null;
 }
 
 const DEFAULT_SETTINGS: AppSettings = {
-  warningThreshold: 90,
+  thresholdTiers: [
+    { threshold: 70, severity: 'info', channels: ['system'] },
+    { threshold: 85, severity: 'warning', channels: ['system', 'wechatWork'] },
+    { threshold: 95, severity: 'critical', channels: ['system', 'wechatWork'] },
+  ],
   checkInterval: 30,
   wechatWorkWebhookUrl: '',
   language: 'zh',
 };
 
+// 设置面板中直接显示在表单项下方的错误字段
+const DISPLAYED_ERROR_FIELDS = /^(checkInterval|wechatWorkWebhookUrl|thresholdTiers\[\d+\]\.threshold)$/;
+
+// 单个字段的校验错误（与后端 validation::FieldError 对应）
+type FieldError = { field: string; code: string; params?: Record<string, string>; message: string };
+
+// save_settings 的错误（与后端 SettingsError 对应）
+type SettingsError =
+  | { kind: 'invalid'; errors: FieldError[] }
+  | { kind: 'storage'; message: string };
+
+/**
+ * 校验错误的本地化文案，没有对应文案的错误代码回退到后端的中文说明
+ */
+function fieldErrorText(error: FieldError): string {
+  const key = `fieldError${error.code.charAt(0).toUpperCase()}${error.code.slice(1)}`;
+  const text = t(key, error.params);
+  return text === key ? error.message : text;
+}
+
+// 告警级别对应的文案 key
+const SEVERITY_LABEL_KEYS: Record<Severity, string> = {
+  info: 'severityInfo',
+  warning: 'severityWarning',
+  critical: 'severityCritical',
+};
+
 /**
  * 主应用组件
  */
@@ -47,6 +132,10 @@ function App() {
   const [currentLang, setCurrentLang] = useState<Language>('zh');
   // 设置数据
   const [settings, setSettings] = useState<AppSettings>(DEFAULT_SETTINGS);
+  // 后端校验返回的字段错误（字段路径 -> 错误说明）
+  const [fieldErrors, setFieldErrors] = useState<Record<string, string>>({});
+  // 全部告警静音的结束时间
+  const [snoozedUntil, setSnoozedUntil] = useState<Date | null>(null);
 
   // 使用 ref 存储最新的输入值，避免闭包问题
   const settingsRef = useRef(settings);
@@ -160,13 +249,8 @@ function App() {
       const result = await invoke<AppSettings>('get_settings');
       console.log('[App] 后端返回配置:', result);
 
-      // 将后端返回的 snake_case 转换为前端使用的 camelCase
-      const convertedSettings = {
-        warningThreshold: result.warningThreshold,
-        checkInterval: result.checkInterval,
-        wechatWorkWebhookUrl: result.wechatWorkWebhookUrl,
-        language: result.language,
-      };
+      // 保留后端返回的全部字段（包括界面未展示的高级设置），避免保存时被覆盖为默认值
+      const convertedSettings = { ...result };
 
       setSettings(prev => ({ ...prev, ...convertedSettings }));
       setCurrentLang(convertedSettings.language as Language || 'zh');
@@ -182,6 +266,30 @@ function App() {
     }
   }, []);
 
+  // 配置生效后（保存、局部更新、导入、切换方案、恢复备份、恢复出厂设置，或配置文件在外部被修改并通过校验）
+  // 后端会发送 settings-changed 事件，同步到界面
+  useEffect(() => {
+    let isMounted = true;
+    let unlisten: (() => void) | undefined;
+
+    listen<AppSettings>('settings-changed', (event) => {
+      console.log('[App] 配置已更新:', event.payload);
+      setSettings(prev => ({ ...prev, ...event.payload }));
+      setCurrentLang(event.payload.language as Language || 'zh');
+    }).then((unlistenFn) => {
+      if (isMounted) {
+        unlisten = unlistenFn;
+      } else {
+        unlistenFn();
+      }
+    });
+
+    return () => {
+      isMounted = false;
+      unlisten?.();
+    };
+  }, []);
+
   /**
    * 切换语言
    */
@@ -190,17 +298,17 @@ function App() {
     setCurrentLang(newLang);
     setLang(newLang);
 
-    const settingsToSave = { ...settings, language: newLang };
     console.log('[App] 语言已切换:', newLang);
 
     try {
-      await invoke('save_settings', { settings: settingsToSave });
+      // 只提交变更的字段，避免覆盖其他窗口或外部编辑的配置
+      await invoke('patch_settings', { patch: { language: newLang } });
       console.log('[App] 语言设置已保存');
       setSettings(prev => ({ ...prev, language: newLang }));
     } catch (err) {
       console.error('[App] 保存语言设置失败:', err);
     }
-  }, [currentLang, settings]);
+  }, [currentLang]);
 
   /**
    * 从页面获取使用量
@@ -211,6 +319,53 @@ function App() {
     console.log('[App] 等待页面自动同步使用量（登录后几秒内会更新）');
   }, [executeScriptAndFetch]);
 
+  /**
+   * 确认严重告警，停止后端的重复提醒
+   */
+  const handleAcknowledgeAlert = useCallback(async () => {
+    try {
+      const acknowledged = await invoke<boolean>('acknowledge_alert');
+      console.log('[App] 确认告警结果:', acknowledged);
+    } catch (err) {
+      console.error('[App] 确认告警失败:', err);
+    }
+  }, []);
+
+  /**
+   * 根据后端返回的静音列表更新全部静音的结束时间
+   */
+  const applySnoozeStatus = useCallback((snoozes: ActiveSnooze[]) => {
+    const all = snoozes.find(snooze => snooze.tier === null);
+    setSnoozedUntil(all ? new Date(all.until) : null);
+  }, []);
+
+  // 启动时读取静音状态，静音到期后清除
+  useEffect(() => {
+    invoke<ActiveSnooze[]>('get_snooze_status')
+      .then(applySnoozeStatus)
+      .catch(err => console.error('[App] 读取静音状态失败:', err));
+  }, [applySnoozeStatus]);
+
+  useEffect(() => {
+    if (!snoozedUntil) return;
+    const timer = setTimeout(() => setSnoozedUntil(null), Math.max(0, snoozedUntil.getTime() - Date.now()));
+    return () => clearTimeout(timer);
+  }, [snoozedUntil]);
+
+  /**
+   * 切换全部告警静音（静音 1 小时 / 取消静音）
+   */
+  const handleToggleSnooze = useCallback(async () => {
+    try {
+      const snoozes = snoozedUntil
+        ? await invoke<ActiveSnooze[]>('unsnooze_alerts', { tier: null })
+        : await invoke<ActiveSnooze[]>('snooze_alerts', { durationMinutes: 60, tier: null });
+      applySnoozeStatus(snoozes);
+    } catch (err) {
+      console.error('[App] 切换告警静音失败:', err);
+    }
+  }, [snoozedUntil, applySnoozeStatus]);
+
   /**
    * 切换设置面板显示
    */
@@ -231,29 +386,28 @@ function App() {
 
     // 使用 ref 获取最新的输入值，避免闭包问题
     const currentSettings = settingsRef.current;
-    const currentThreshold = currentSettings.warningThreshold;
+    const currentTiers = currentSettings.thresholdTiers;
     const currentInterval = currentSettings.checkInterval;
 
-    console.log('[App] 当前输入值 - 阈值:', currentThreshold, '间隔:', currentInterval);
+    console.log('[App] 当前输入值 - 阈值级别:', currentTiers, '间隔:', currentInterval);
 
     if (!Number.isFinite(currentInterval) || currentInterval <= 0) {
       alert(t('invalidInterval'));
       return;
     }
 
-    // 计算修正后的阈值
-    let sanitizedThreshold = currentThreshold;
-    if (!Number.isFinite(currentThreshold)) {
-      sanitizedThreshold = 90;
-    } else {
-      sanitizedThreshold = Math.min(100, Math.max(0, currentThreshold));
-    }
+    // 计算修正后的阈值（每个级别限制在 0~100）
+    const sanitizedTiers = currentTiers.map(tier => ({
+      ...tier,
+      threshold: Number.isFinite(tier.threshold) ? Math.min(100, Math.max(0, tier.threshold)) : 90,
+    }));
 
-    console.log('[App] 修正后的阈值:', sanitizedThreshold);
+    console.log('[App] 修正后的阈值级别:', sanitizedTiers);
 
     // 构造保存的配置对象
     const settingsToSave = {
-      warningThreshold: sanitizedThreshold,
+      ...currentSettings,
+      thresholdTiers: sanitizedTiers,
       checkInterval: currentInterval,
       wechatWorkWebhookUrl: currentSettings.wechatWorkWebhookUrl,
       language: currentSettings.language,
@@ -264,11 +418,12 @@ function App() {
     try {
       await invoke('save_settings', { settings: settingsToSave });
       console.log('[App] 设置已保存到后端');
+      setFieldErrors({});
 
       // 保存成功后更新本地 state（确保一致性）
       setSettings(prev => ({
         ...prev,
-        warningThreshold: sanitizedThreshold,
+        thresholdTiers: sanitizedTiers,
         checkInterval: currentInterval,
       }));
       console.log('[App] 本地 state 已更新');
@@ -278,6 +433,12 @@ function App() {
       console.log('[App] 配置已验证');
     } catch (err) {
       console.error('[App] 保存设置失败:', err);
+      // 校验失败时保持设置面板打开，并在对应表单项下显示错误
+      const settingsError = err as SettingsError;
+      if (settingsError?.kind === 'invalid') {
+        setFieldErrors(Object.fromEntries(settingsError.errors.map(e => [e.field, fieldErrorText(e)])));
+        return;
+      }
       // 后端保存失败时，回退到 localStorage
       localStorage.setItem('minmax_settings', JSON.stringify(settingsToSave));
     }
@@ -290,6 +451,7 @@ function App() {
    */
   const cancelSettings = useCallback(() => {
     console.log('[App] 取消设置修改');
+    setFieldErrors({});
     setShowSettings(false);
     loadSettingsFromBackend();
   }, [loadSettingsFromBackend]);
@@ -345,6 +507,48 @@ function App() {
     }
   }, []);
 
+  /**
+   * 导出不含密钥的配置预设，供团队成员导入
+   */
+  const exportPreset = useCallback(async () => {
+    try {
+      const content = await invoke<string>('export_settings', { includeSecrets: false });
+      const url = URL.createObjectURL(new Blob([content], { type: 'application/json' }));
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = 'minmax-helper-preset.json';
+      link.click();
+      URL.revokeObjectURL(url);
+      console.log('[App] 配置预设已导出');
+    } catch (err) {
+      console.error('[App] 导出配置预设失败:', err);
+    }
+  }, []);
+
+  /**
+   * 导入配置预设，保留本机的 Webhook 等密钥
+   */
+  const importPreset = useCallback(async (e: React.ChangeEvent<HTMLInputElement>) => {
+    const file = e.target.files?.[0];
+    e.target.value = '';
+    if (!file) return;
+
+    try {
+      const content = await file.text();
+      const result = await invoke<AppSettings>('import_settings', { content });
+      console.log('[App] 配置预设已导入:', result);
+      setFieldErrors({});
+      setSettings(prev => ({ ...prev, ...result }));
+      setCurrentLang(result.language as Language || 'zh');
+    } catch (err) {
+      console.error('[App] 导入配置预设失败:', err);
+      const settingsError = err as SettingsError;
+      if (settingsError?.kind === 'invalid') {
+        setFieldErrors(Object.fromEntries(settingsError.errors.map(e => [e.field, fieldErrorText(e)])));
+      }
+    }
+  }, []);
+
   /**
    * 测试通知（同时发送系统通知和企业微信通知）
    */
@@ -361,11 +565,14 @@ function App() {
   /**
    * 输入处理函数
    */
-  const handleWarningThresholdChange = useCallback((e: React.ChangeEvent<HTMLInputElement>) => {
+  const handleTierThresholdChange = useCallback((index: number, e: React.ChangeEvent<HTMLInputElement>) => {
     const value = parseFloat(e.target.value);
     const newValue = isNaN(value) ? 0 : value;
-    console.log('[App] 阈值输入变化:', value, '->', newValue);
-    setSettings(prev => ({ ...prev, warningThreshold: newValue }));
+    console.log('[App] 阈值级别', index, '输入变化:', value, '->', newValue);
+    setSettings(prev => ({
+      ...prev,
+      thresholdTiers: prev.thresholdTiers.map((tier, i) => (i === index ? { ...tier, threshold: newValue } : tier)),
+    }));
   }, []);
 
   const handleCheckIntervalChange = useCallback((e: React.ChangeEvent<HTMLInputElement>) => {
@@ -429,14 +636,22 @@ function App() {
 
             <div className="flex flex-col gap-2">
               <label className="text-[13px] font-medium text-gray-600">{t('warningThreshold')}</label>
-              <input
-                type="number"
-                value={settings.warningThreshold}
-                onChange={handleWarningThresholdChange}
-                min={0}
-                max={100}
-                className="w-full px-[14px] py-[10px] border border-gray-200 rounded-xl outline-none transition-all duration-200 bg-white/90 focus:border-purple-500 focus:shadow-[0_0_0_3px_rgba(102,126,234,0.2)]"
-              />
+              {settings.thresholdTiers.map((tier, index) => (
+                <div key={index} className="flex items-center gap-2">
+                  <span className="text-[12px] text-gray-500 min-w-[48px]">{t(SEVERITY_LABEL_KEYS[tier.severity])}</span>
+                  <input
+                    type="number"
+                    value={tier.threshold}
+                    onChange={(e) => handleTierThresholdChange(index, e)}
+                    min={0}
+                    max={100}
+                    className="w-full px-[14px] py-[10px] border border-gray-200 rounded-xl outline-none transition-all duration-200 bg-white/90 focus:border-purple-500 focus:shadow-[0_0_0_3px_rgba(102,126,234,0.2)]"
+                  />
+                  {fieldErrors[`thresholdTiers[${index}].threshold`] && (
+                    <span className="text-[12px] text-red-500 whitespace-nowrap">{fieldErrors[`thresholdTiers[${index}].threshold`]}</span>
+                  )}
+                </div>
+              ))}
             </div>
 
             <div className="flex flex-col gap-2">
@@ -448,6 +663,9 @@ function App() {
                 min={1}
                 className="w-full px-[14px] py-[10px] border border-gray-200 rounded-xl outline-none transition-all duration-200 bg-white/90 focus:border-purple-500 focus:shadow-[0_0_0_3px_rgba(102,126,234,0.2)]"
               />
+              {fieldErrors.checkInterval && (
+                <span className="text-[12px] text-red-500">{fieldErrors.checkInterval}</span>
+              )}
             </div>
 
             <div className="flex flex-col gap-2">
@@ -459,6 +677,9 @@ function App() {
                 className="w-full px-[14px] py-[10px] border border-gray-200 rounded-xl outline-none transition-all duration-200 bg-white/90 focus:border-purple-500 focus:shadow-[0_0_0_3px_rgba(102,126,234,0.2)]"
                 placeholder={t('wechatWorkWebhookUrlPlaceholder')}
               />
+              {fieldErrors.wechatWorkWebhookUrl && (
+                <span className="text-[12px] text-red-500">{fieldErrors.wechatWorkWebhookUrl}</span>
+              )}
             </div>
 
             <div className="flex flex-col gap-2">
@@ -470,6 +691,19 @@ function App() {
               </button>
             </div>
 
+            <div className="flex gap-3">
+              <button
+                onClick={exportPreset}
+                className="flex-1 px-5 py-3 bg-gray-100 text-gray-600 rounded-xl font-medium text-[14px] cursor-pointer border border-gray-200 transition-all duration-200 hover:bg-gray-200 hover:-translate-y-0.5"
+              >
+                {t('exportPreset')}
+              </button>
+              <label className="flex-1 px-5 py-3 bg-gray-100 text-gray-600 rounded-xl font-medium text-[14px] text-center cursor-pointer border border-gray-200 transition-all duration-200 hover:bg-gray-200 hover:-translate-y-0.5">
+                {t('importPreset')}
+                <input type="file" accept="application/json,.json" onChange={importPreset} className="hidden" />
+              </label>
+            </div>
+
             <div className="flex flex-col gap-2">
               <button
                 onClick={resetToFactorySettings}
@@ -479,6 +713,15 @@ function App() {
               </button>
             </div>
 
+            {/* 界面上没有对应表单项的校验错误 */}
+            {Object.entries(fieldErrors)
+              .filter(([field]) => !DISPLAYED_ERROR_FIELDS.test(field))
+              .map(([field, message]) => (
+                <div key={field} className="text-[12px] text-red-500">
+                  {field}: {message}
+                </div>
+              ))}
+
             <div className="flex gap-3 mt-2">
               <button
                 onClick={saveSettings}
@@ -503,10 +746,13 @@ function App() {
             error={displayError}
             notificationStatus={displayNotificationStatus}
             isOverThreshold={isOverThreshold}
-            threshold={settings.warningThreshold}
+            threshold={getLowestThreshold(settings.thresholdTiers) ?? 0}
             t={t}
             lastUpdateTime={lastUpdateTime}
             onFetchUsage={handleFetchUsage}
+            onAcknowledgeAlert={handleAcknowledgeAlert}
+            snoozedUntil={snoozedUntil}
+            onToggleSnooze={handleToggleSnooze}
             statusColor={statusColor}
             statusIcon={statusIcon}
           />
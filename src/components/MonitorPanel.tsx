@@ -26,6 +26,12 @@ interface MonitorPanelProps {
   lastUpdateTime: Date | null;
   // 获取使用量事件
   onFetchUsage: () => void;
+  // 确认严重告警事件（停止重复提醒）
+  onAcknowledgeAlert?: () => void;
+  // 全部告警静音的结束时间，未静音时为 null
+  snoozedUntil?: Date | null;
+  // 切换告警静音事件
+  onToggleSnooze?: () => void;
   // 状态颜色
   statusColor?: string;
   // 状态图标
@@ -48,6 +54,9 @@ export function MonitorPanel({
   t,
   lastUpdateTime,
   onFetchUsage,
+  onAcknowledgeAlert,
+  snoozedUntil = null,
+  onToggleSnooze,
   statusColor = '#667eea',
   statusIcon = '📊',
 }: MonitorPanelProps) {
@@ -146,6 +155,28 @@ export function MonitorPanel({
           </span>
         </div>
 
+        {/* 确认告警（停止严重告警的重复提醒） */}
+        {isOverThreshold && onAcknowledgeAlert && (
+          <button
+            onClick={onAcknowledgeAlert}
+            className="px-4 py-2 text-xs text-red-500 bg-red-500/10 hover:bg-red-500/20 rounded-full transition-all duration-200"
+          >
+            {t('acknowledgeAlert')}
+          </button>
+        )}
+
+        {/* 告警静音 */}
+        {onToggleSnooze && (
+          <button
+            onClick={onToggleSnooze}
+            className="px-4 py-2 text-xs text-gray-500 bg-black/5 hover:bg-black/10 rounded-full transition-all duration-200"
+          >
+            {snoozedUntil
+              ? t('unsnoozeAlerts', { time: formatTime(snoozedUntil) })
+              : t('snoozeAlerts')}
+          </button>
+        )}
+
         {/* 剩余重置时间 */}
         {resetTime && (
           <div className="flex items-center gap-1.5 text-xs text-gray-400 px-4 py-2 bg-black/3 rounded-full">
@@ -6,9 +6,29 @@ import { invoke } from '@tauri-apps/api/core';
  * 封装使用量数据的获取逻辑和状态管理
  */
 
+// 告警级别
+export type Severity = 'info' | 'warning' | 'critical';
+
+// 通知渠道
+export type NotificationChannel = 'system' | 'wechatWork';
+
+// 阈值级别（与后端 alerts::ThresholdTier 对应）
+export interface ThresholdTier {
+  threshold: number;
+  severity: Severity;
+  messageTemplate?: string;
+  channels?: NotificationChannel[];
+}
+
+// 生效中的告警静音（与后端 alerts::ActiveSnooze 对应），tier 为 null 表示全部级别
+export interface ActiveSnooze {
+  tier: Severity | null;
+  until: number;
+}
+
 // 设置数据接口
 export interface Settings {
-  warningThreshold: number;
+  thresholdTiers: ThresholdTier[];
   checkInterval: number;
   wechatWorkWebhookUrl: string;
   language: string;
@@ -29,6 +49,16 @@ export interface UseUsageReturn {
   resetWarningState: () => void;
 }
 
+/**
+ * 获取最低的阈值，用于判断是否需要预警
+ * @param tiers 阈值级别列表
+ * @returns 最低阈值，没有配置任何级别时返回 null
+ */
+export function getLowestThreshold(tiers: ThresholdTier[] | undefined): number | null {
+  if (!tiers || tiers.length === 0) return null;
+  return Math.min(...tiers.map(tier => tier.threshold));
+}
+
 /**
  * 从文本中提取使用量百分比
  * 参考 Chrome 扩展的 content script 逻辑
@@ -106,21 +136,20 @@ async function pasteFromClipboard(): Promise<number | null> {
 
 /**
  * 发送预警通知
+ * 后端根据使用量匹配阈值级别并返回触发的级别
  * @param percent 当前使用量百分比
- * @param threshold 预警阈值
+ * @returns 触发的阈值级别；未达到阈值时为 null；发送失败时为 undefined
  */
-async function sendWarningNotification(percent: number, threshold: number): Promise<boolean> {
+async function sendWarningNotification(percent: number): Promise<ThresholdTier | null | undefined> {
   console.log('[useUsage] 发送预警通知，使用量:', percent + '%');
 
   try {
-    await invoke('send_warning_notification', {
+    return await invoke<ThresholdTier | null>('send_warning_notification', {
       usage: percent,
-      threshold,
     });
-    return true;
   } catch (err) {
     console.error('[useUsage] 发送预警通知失败:', err);
-    return false;
+    return undefined;
   }
 }
 
@@ -160,20 +189,21 @@ export function useUsage(settings: Settings): UseUsageReturn {
 
     console.log('[useUsage] 获取到使用量:', percent + '%');
 
-    // 检查是否需要发送预警通知（每次超过阈值都发送）
-    const shouldNotify = percent >= settings.warningThreshold;
+    // 检查是否需要发送预警通知（每次超过最低阈值都发送，由后端决定触发哪个级别）
+    const lowestThreshold = getLowestThreshold(settings.thresholdTiers);
+    const shouldNotify = lowestThreshold !== null && percent >= lowestThreshold;
 
     // 如果使用量超过阈值，发送预警通知
     if (shouldNotify) {
       console.log('[useUsage] 使用量超过阈值，触发预警:', {
         percent,
-        threshold: settings.warningThreshold,
+        threshold: lowestThreshold,
       });
 
-      const success = await sendWarningNotification(percent, settings.warningThreshold);
+      const tier = await sendWarningNotification(percent);
 
-      if (success) {
-        const statusMessage = `⚠️ 预警已发送 (${percent.toFixed(1)}%)`;
+      if (tier) {
+        const statusMessage = `⚠️ 预警已发送 (${tier.severity} ${percent.toFixed(1)}%)`;
         setNotificationStatus(statusMessage);
 
         // 3秒后清除状态提示
@@ -183,11 +213,11 @@ export function useUsage(settings: Settings): UseUsageReturn {
         timeoutRef.current = setTimeout(() => {
           setNotificationStatus('');
         }, 3000);
-      } else {
+      } else if (tier === undefined) {
         setNotificationStatus('预警发送失败');
       }
     }
-  }, [settings.warningThreshold]);
+  }, [settings.thresholdTiers]);
 
   /**
    * 从剪贴板获取使用量数据
@@ -241,7 +271,8 @@ export function useUsage(settings: Settings): UseUsageReturn {
   /**
    * 检查使用量是否超过阈值
    */
-  const isOverThreshold = usagePercent !== null && usagePercent >= settings.warningThreshold;
+  const lowestThreshold = getLowestThreshold(settings.thresholdTiers);
+  const isOverThreshold = usagePercent !== null && lowestThreshold !== null && usagePercent >= lowestThreshold;
 
   /**
    * 重置预警状态（用于测试或手动清除）
@@ -19,6 +19,12 @@ const messages: Record<Lang, Messages> = {
     toggleLanguage: '切换语言',
     settingsTitle: '设置',
     warningThreshold: '预警阈值 (%)',
+    severityInfo: '提醒',
+    severityWarning: '预警',
+    severityCritical: '严重',
+    acknowledgeAlert: '确认告警，停止重复提醒',
+    snoozeAlerts: '静音 1 小时',
+    unsnoozeAlerts: '取消静音（至 {time}）',
     checkInterval: '后台检查间隔 (分钟)',
     save: '保存',
     cancel: '取消',
@@ -32,6 +38,8 @@ const messages: Record<Lang, Messages> = {
     backToMonitor: '返回监控',
     invalidInterval: '后台检查间隔必须大于 0 分钟',
     testNotification: '测试系统通知',
+    exportPreset: '导出预设（不含密钥）',
+    importPreset: '导入预设',
     notificationSent: '通知已发送',
     wechatWorkWebhookUrl: '企业微信 Webhook URL',
     wechatWorkWebhookUrlPlaceholder: 'https://qyapi.weixin.qq.com/... (可选)',
@@ -62,6 +70,21 @@ const messages: Record<Lang, Messages> = {
     checkingLogin: '正在检查登录状态...',
     loginRequired: '请先登录 MiniMax',
     goToLogin: '去登录',
+    fieldErrorRange: '必须在 {min} 到 {max} 之间',
+    fieldErrorExclusiveRange: '必须大于 {min} 且不超过 {max}',
+    fieldErrorPositive: '必须大于 0',
+    fieldErrorNonNegative: '不能为负数',
+    fieldErrorChannelsRequired: '至少选择一个通知渠道',
+    fieldErrorInvalidUrl: 'URL 格式无效: {reason}',
+    fieldErrorUrlScheme: 'URL 必须以 http:// 或 https:// 开头',
+    fieldErrorUrlHost: 'URL 缺少主机名',
+    fieldErrorUnsupportedLanguage: '不支持的语言 {value}，可选: {options}',
+    fieldErrorInvalidProfileName: '方案名称无效',
+    fieldErrorRuleNameRequired: '规则名称不能为空',
+    fieldErrorDuplicateRuleName: '规则名称重复: {name}',
+    fieldErrorDuplicateThreshold: '阈值重复: {value}%',
+    fieldErrorInvalidCondition: '规则条件无效: {detail}',
+    fieldErrorUnknownField: '未知的配置字段',
   },
   en: {
     title: 'MiniMax Usage Monitor',
@@ -69,6 +92,12 @@ const messages: Record<Lang, Messages> = {
     toggleLanguage: 'Toggle Language',
     settingsTitle: 'Settings',
     warningThreshold: 'Warning Threshold (%)',
+    severityInfo: 'Info',
+    severityWarning: 'Warning',
+    severityCritical: 'Critical',
+    acknowledgeAlert: 'Acknowledge alert',
+    snoozeAlerts: 'Snooze 1 hour',
+    unsnoozeAlerts: 'Unsnooze (until {time})',
     checkInterval: 'Check Interval (min)',
     save: 'Save',
     cancel: 'Cancel',
@@ -82,6 +111,8 @@ const messages: Record<Lang, Messages> = {
     backToMonitor: 'Back to Monitor',
     invalidInterval: 'Check interval must be greater than 0 minutes',
     testNotification: 'Test Notification',
+    exportPreset: 'Export preset (without secrets)',
+    importPreset: 'Import preset',
     notificationSent: 'Notification Sent',
     wechatWorkWebhookUrl: 'WeChat Work Webhook URL',
     wechatWorkWebhookUrlPlaceholder: 'https://qyapi.weixin.qq.com/... (optional)',
@@ -112,6 +143,21 @@ const messages: Record<Lang, Messages> = {
     checkingLogin: 'Checking login status...',
     loginRequired: 'Please login to MiniMax first',
     goToLogin: 'Go to Login',
+    fieldErrorRange: 'Must be between {min} and {max}',
+    fieldErrorExclusiveRange: 'Must be greater than {min} and at most {max}',
+    fieldErrorPositive: 'Must be greater than 0',
+    fieldErrorNonNegative: 'Must not be negative',
+    fieldErrorChannelsRequired: 'Select at least one notification channel',
+    fieldErrorInvalidUrl: 'Invalid URL: {reason}',
+    fieldErrorUrlScheme: 'URL must start with http:// or https://',
+    fieldErrorUrlHost: 'URL is missing a host name',
+    fieldErrorUnsupportedLanguage: 'Unsupported language {value}, expected one of: {options}',
+    fieldErrorInvalidProfileName: 'Invalid profile name',
+    fieldErrorRuleNameRequired: 'Rule name must not be empty',
+    fieldErrorDuplicateRuleName: 'Duplicate rule name: {name}',
+    fieldErrorDuplicateThreshold: 'Duplicate threshold: {value}%',
+    fieldErrorInvalidCondition: 'Invalid rule condition: {detail}',
+    fieldErrorUnknownField: 'Unknown setting',
   },
 };
 